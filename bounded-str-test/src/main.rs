@@ -137,7 +137,7 @@ let ascii = r#"
 mod tests {
     use super::*;
     use serde_json::json;
-	use bounded_str::BoundedStrError;
+	use bounded_str::{BoundedStrError, MutationErrorKind};
     type Username = BoundedStr<3, 16, 32, bounded_str::Chars, bounded_str::AsciiOnly>;
     type Token = BoundedStr<1, 128, 128, bounded_str::Chars, TokenPolicy>;
 
@@ -156,33 +156,1330 @@ mod tests {
     #[test]
     fn test_username_too_short() {
         let err = Username::new("Al").unwrap_err();
-        assert!(matches!(err, BoundedStrError::TooShort));
+        assert!(matches!(err, BoundedStrError::TooShort { .. }));
     }
 
     #[test]
     fn test_username_too_long() {
         let long = "A".repeat(17);
         let err = Username::new(&long).unwrap_err();
-        assert!(matches!(err, BoundedStrError::TooLong));
+        assert!(matches!(err, BoundedStrError::TooLong { .. }));
     }
 
     #[test]
     fn test_username_invalid_chars() {
         let err = Username::new("Bob🔥").unwrap_err();
-        assert!(matches!(err, BoundedStrError::InvalidContent));
+        assert!(matches!(err, BoundedStrError::InvalidContent { .. }));
     }
 
     #[test]
     fn test_token_too_long() {
         let long_token = "a".repeat(129);
         let err = Token::new(&long_token).unwrap_err();
-        assert!(matches!(err, BoundedStrError::TooLong | BoundedStrError::TooManyBytes));
+        assert!(matches!(err, BoundedStrError::TooLong { .. } | BoundedStrError::TooManyBytes { .. }));
     }
 
     #[test]
     fn test_token_invalid_chars() {
         let err = Token::new("🔥🔥🔥").unwrap_err();
-        assert!(matches!(err, BoundedStrError::InvalidContent));
+        assert!(matches!(err, BoundedStrError::InvalidContent { .. }));
+    }
+
+    #[test]
+    fn test_username_trimmed() {
+        let u = Username::new_trimmed("  Alice \t\n").expect("Trimmed username should pass");
+        assert_eq!(u.as_str(), "Alice");
+
+        let err = Username::new_trimmed("   Al   ").unwrap_err();
+        assert!(matches!(err, BoundedStrError::TooShort { .. }));
+    }
+
+    #[test]
+    fn test_username_truncated() {
+        let u = Username::new_truncate("Alice_and_her_long_nickname").expect("Long username is truncated");
+        assert_eq!(u.as_str(), "Alice_and_her_lo");
+        assert_eq!(u.len_logical(), 16);
+
+        let err = Username::new_truncate("Al").unwrap_err();
+        assert!(matches!(err, BoundedStrError::TooShort { .. }));
+    }
+
+    #[test]
+    fn test_token_from_utf8() {
+        let t = Token::from_utf8(b"abc123").expect("Valid UTF-8 token");
+        assert_eq!(t.as_str(), "abc123");
+
+        let err = Token::from_utf8(b"abc\xFF").unwrap_err();
+        assert!(matches!(err, BoundedStrError::InvalidUtf8));
+    }
+
+    #[test]
+    fn test_from_utf8_lossy() {
+        type Text = BoundedStr<1, 8, 32, bounded_str::Chars>;
+        let t = Text::from_utf8_lossy(b"ab\xF0\x90cd").expect("Lossy decode");
+        assert_eq!(t.as_str(), "ab\u{FFFD}cd");
+
+        let err = Token::from_utf8_lossy(b"abc\xFF").unwrap_err();
+        assert!(matches!(err, BoundedStrError::InvalidContent { .. }));
+    }
+
+    #[test]
+    fn test_from_utf16() {
+        type Text = BoundedStr<1, 8, 32, bounded_str::Chars>;
+        let units: Vec<u16> = "hi🔥".encode_utf16().collect();
+        let t = Text::from_utf16(&units).expect("Valid UTF-16");
+        assert_eq!(t.as_str(), "hi🔥");
+
+        let broken = [0x0068, 0xD83D, 0x0069];
+        assert!(matches!(Text::from_utf16(&broken), Err(BoundedStrError::InvalidUtf16)));
+        assert_eq!(Text::from_utf16_lossy(&broken).unwrap().as_str(), "h\u{FFFD}i");
+    }
+
+    #[test]
+    fn test_username_ordering() {
+        let mut names: Vec<Username> = ["carol", "alice", "bob"]
+            .iter()
+            .map(|n| Username::new(n).unwrap())
+            .collect();
+        names.sort();
+        assert_eq!(names, ["alice", "bob", "carol"]);
+        assert!(names[0] < "alicia");
+
+        let map: std::collections::BTreeMap<Username, u32> =
+            names.into_iter().zip(1..).collect();
+        assert_eq!(map.keys().next().unwrap().as_str(), "alice");
+    }
+
+    #[test]
+    fn test_username_map_lookup() {
+        let mut roles = std::collections::HashMap::new();
+        roles.insert(Username::new("alice").unwrap(), "admin");
+        assert_eq!(roles.get("alice"), Some(&"admin"));
+        assert_eq!(roles.get("bob"), None);
+
+        fn shout(s: impl AsRef<str>) -> String { s.as_ref().to_uppercase() }
+        let u = Username::new("alice").unwrap();
+        assert_eq!(shout(&u), "ALICE");
+        assert_eq!(AsRef::<[u8]>::as_ref(&u), b"alice");
+    }
+
+    #[test]
+    fn test_username_slicing() {
+        let u = Username::new("alice_42").unwrap();
+        assert_eq!(&u[..5], "alice");
+        assert_eq!(&u[6..], "42");
+        assert_eq!(&u[1..=3], "lic");
+        assert_eq!(u.get(6..8), Some("42"));
+        assert_eq!(u.get(6..20), None);
+    }
+
+    #[test]
+    fn test_cross_type_comparison() {
+        type Login = BoundedStr<1, 64, 64>;
+        let u = Username::new("alice").unwrap();
+        let l = Login::new("alice").unwrap();
+        let t = Token::new("bob").unwrap();
+
+        assert!(u == l);
+        assert!(l == u);
+        assert!(u != t);
+        assert!(u < t);
+        assert!(t > l);
+    }
+
+    #[test]
+    fn test_string_comparison_both_ways() {
+        let u = Username::new("alice").unwrap();
+        let owned = String::from("alice");
+
+        assert_eq!(u, owned);
+        assert_eq!(owned, u);
+        assert_eq!("alice", u);
+        assert_ne!("bob", u);
+    }
+
+    #[test]
+    fn test_display_respects_formatter_flags() {
+        let u = Username::new("alice").unwrap();
+        assert_eq!(format!("[{:>8}]", u), "[   alice]");
+        assert_eq!(format!("[{:<8}]", u), "[alice   ]");
+        assert_eq!(format!("[{:*^9}]", u), "[**alice**]");
+        assert_eq!(format!("[{:.3}]", u), "[ali]");
+        assert_eq!(format!("[{}]", u), "[alice]");
+    }
+
+    #[test]
+    fn test_error_display_and_boxing() {
+        fn parse(name: &str) -> Result<Username, Box<dyn std::error::Error>> {
+            Ok(Username::new(name)?)
+        }
+
+        let err = parse("Al").unwrap_err();
+        assert_eq!(err.to_string(), "string too short (length 2, min 3)");
+        let err = BoundedStrError::MutationFailed { kind: MutationErrorKind::PolicyViolation };
+        assert_eq!(err.to_string(), "mutation failed: format policy violated");
+    }
+
+    #[test]
+    fn test_error_diagnostics() {
+        assert_eq!(Username::new("Al").unwrap_err(), BoundedStrError::TooShort { actual: 2, min: 3 });
+        assert_eq!(
+            Username::new(&"A".repeat(17)).unwrap_err(),
+            BoundedStrError::TooLong { actual: 17, max: 16 }
+        );
+
+        let err = Username::new("Bob🔥x").unwrap_err();
+        assert_eq!(err, BoundedStrError::InvalidContent { byte_offset: 3, char: Some('🔥') });
+        assert_eq!(err.to_string(), "invalid content format ('🔥' at byte 3)");
+
+        // Custom policies without `invalid_at` cannot point at the offending char.
+        let err = Token::new("abc-def").unwrap_err();
+        assert_eq!(err, BoundedStrError::InvalidContent { byte_offset: 0, char: None });
+    }
+
+    #[test]
+    fn test_deserialize_owned_sources() {
+        // Readers cannot hand out borrowed strings.
+        let u: Username = serde_json::from_reader(&b"\"Alice\""[..]).expect("reader should parse");
+        assert_eq!(u, "Alice");
+
+        // Escapes force serde_json to unescape into a scratch buffer.
+        let u: Username = serde_json::from_str(r#""Al\u0069ce""#).expect("escaped should parse");
+        assert_eq!(u, "Alice");
+
+        // Values hand over an owned String.
+        let u: Username = serde_json::from_value(json!("Alice")).expect("value should parse");
+        assert_eq!(u, "Alice");
+
+        let err = serde_json::from_value::<Username>(json!("Al")).unwrap_err();
+        assert_eq!(err.to_string(), "string too short: 2 chars, min 3");
+        assert!(serde_json::from_value::<Username>(json!(42)).is_err());
+    }
+
+    #[test]
+    fn test_deserialize_error_context() {
+        type Nick = bounded_str::StackStr<1, 16, 64, bounded_str::Chars>;
+        let long = "Ж".repeat(24);
+        let err = serde_json::from_value::<Nick>(json!(long)).unwrap_err();
+        assert_eq!(err.to_string(), "string too long: 24 chars, max 16");
+
+        let err = serde_json::from_str::<Username>(r#""Bob🔥""#).unwrap_err();
+        assert!(err.to_string().starts_with("invalid content format ('🔥' at byte 3) (policy AsciiOnly)"));
+    }
+
+    #[test]
+    fn test_deserialize_coercing_adapters() {
+        #[derive(Deserialize, serde::Serialize)]
+        struct Legacy {
+            #[serde(with = "bounded_str::serde::truncate")]
+            name: Username,
+            #[serde(with = "bounded_str::serde::trimmed")]
+            nick: Username,
+            #[serde(with = "bounded_str::serde::lossy")]
+            note: bounded_str::StackStr<1, 16, 16>,
+        }
+
+        let legacy: Legacy = serde_json::from_str(
+            r#"{"name": "AliceWonderland1234", "nick": "  Bob  ", "note": "ok"}"#,
+        )
+        .expect("coerced");
+        assert_eq!(legacy.name, "AliceWonderland1");
+        assert_eq!(legacy.nick, "Bob");
+        assert_eq!(legacy.note, "ok");
+
+        // Coercion does not bypass the remaining checks.
+        let res = serde_json::from_str::<Legacy>(r#"{"name": "Al", "nick": "Bob", "note": "ok"}"#);
+        assert!(res.is_err());
+
+        let bytes = serde::de::value::BytesDeserializer::<serde::de::value::Error>::new(b"ok\xff");
+        let note: bounded_str::StackStr<1, 16, 16> = bounded_str::serde::lossy::deserialize(bytes).unwrap();
+        assert_eq!(note, "ok\u{FFFD}");
+
+        let out = serde_json::to_string(&legacy).unwrap();
+        assert_eq!(out, r#"{"name":"AliceWonderland1","nick":"Bob","note":"ok"}"#);
+    }
+
+    #[test]
+    fn test_binary_serde_adapters() {
+        type Frame = bounded_str::StackStr<1, 8, 8>;
+
+        #[derive(Deserialize, serde::Serialize)]
+        struct Packet {
+            #[serde(with = "bounded_str::serde::bytes")]
+            name: Frame,
+            #[serde(with = "bounded_str::serde::padded")]
+            tag: Frame,
+        }
+
+        let short = Packet { name: Frame::new("a").unwrap(), tag: Frame::new("b").unwrap() };
+        let long = Packet { name: Frame::new("abcdefgh").unwrap(), tag: Frame::new("abcdefgh").unwrap() };
+        let short_wire = postcard::to_allocvec(&short).unwrap();
+        let long_wire = postcard::to_allocvec(&long).unwrap();
+
+        // Length-prefixed `name` grows with content, the padded `tag` frame does not.
+        assert_eq!(short_wire.len(), 2 + 12);
+        assert_eq!(long_wire.len(), 9 + 12);
+        assert_eq!(&short_wire[2..6], &[1, 0, 0, 0]);
+
+        let back: Packet = postcard::from_bytes(&long_wire).unwrap();
+        assert_eq!(back.name, "abcdefgh");
+        assert_eq!(back.tag, "abcdefgh");
+
+        let mut bad = short_wire.clone();
+        bad[2] = 9; // length prefix beyond MAX_BYTES
+        assert!(postcard::from_bytes::<Packet>(&bad).is_err());
+        let mut bad = short_wire;
+        bad[1] = 0xFF; // invalid utf-8 in the byte buffer
+        assert!(postcard::from_bytes::<Packet>(&bad).is_err());
+    }
+
+    #[test]
+    fn test_borsh_roundtrip() {
+        let u = Username::new("Alice").unwrap();
+        let wire = borsh::to_vec(&u).unwrap();
+        assert_eq!(wire, borsh::to_vec("Alice").unwrap());
+        assert_eq!(borsh::from_slice::<Username>(&wire).unwrap(), "Alice");
+
+        // Decoding enforces the bounds and the policy.
+        assert!(borsh::from_slice::<Username>(&borsh::to_vec("Al").unwrap()).is_err());
+        assert!(borsh::from_slice::<Username>(&borsh::to_vec("Bob🔥").unwrap()).is_err());
+        assert!(borsh::from_slice::<Username>(&[3, 0, 0, 0, b'a', 0xFF, b'c']).is_err());
+
+        // A forged length prefix fails before any payload is read.
+        let mut input: &[u8] = &[0xFF, 0xFF, 0xFF, 0xFF, b'a', b'b'];
+        let err = <Username as borsh::BorshDeserialize>::deserialize_reader(&mut input).unwrap_err();
+        assert_eq!(err.kind(), std::io::ErrorKind::InvalidData);
+        assert!(err.to_string().starts_with("too many bytes"));
+        assert_eq!(input, b"ab");
+        let mut input: &[u8] = &[5, 0, 0, 0, b'A', b'l'];
+        let err = <Username as borsh::BorshDeserialize>::deserialize_reader(&mut input).unwrap_err();
+        assert_eq!(err.kind(), std::io::ErrorKind::UnexpectedEof);
+    }
+
+    #[test]
+    fn test_rkyv_roundtrip() {
+        use rkyv::rancor::Error;
+
+        let u = Username::new("Alice").unwrap();
+        let bytes = rkyv::to_bytes::<Error>(&u).unwrap();
+        let archived = rkyv::access::<rkyv::Archived<Username>, Error>(&bytes).unwrap();
+        assert_eq!(archived, "Alice");
+        assert_eq!(rkyv::deserialize::<Username, Error>(archived).unwrap(), u);
+
+        // A plain archived string that breaks the bounds fails validation.
+        let bytes = rkyv::to_bytes::<Error>(&String::from("Al")).unwrap();
+        assert!(rkyv::access::<rkyv::Archived<Username>, Error>(&bytes).is_err());
+        let bytes = rkyv::to_bytes::<Error>(&String::from("Bob🔥")).unwrap();
+        assert!(rkyv::access::<rkyv::Archived<Username>, Error>(&bytes).is_err());
+    }
+
+    #[test]
+    fn test_json_schema() {
+        #[derive(schemars::JsonSchema)]
+        #[allow(dead_code)]
+        struct SignUp {
+            username: Username,
+            room: bounded_str::StackStr<8, 255, 255>,
+        }
+
+        let schema = schemars::schema_for!(SignUp);
+        let props = &schema.as_value()["properties"];
+        assert_eq!(
+            props["username"],
+            json!({ "type": "string", "minLength": 3, "maxLength": 16, "pattern": "^[\\u0000-\\u007F]*$" })
+        );
+        // Byte bounds only give a code point range.
+        assert_eq!(props["room"], json!({ "type": "string", "minLength": 2, "maxLength": 255 }));
+    }
+
+    #[test]
+    fn test_openapi_schema() {
+        #[derive(utoipa::ToSchema)]
+        #[allow(dead_code)]
+        struct SignUp {
+            username: Username,
+            room: bounded_str::StackStr<8, 255, 255>,
+        }
+
+        let schema = serde_json::to_value(<SignUp as utoipa::PartialSchema>::schema()).unwrap();
+        assert_eq!(
+            schema["properties"]["username"]["$ref"],
+            "#/components/schemas/BoundedStr_3_16_Chars_AsciiOnly"
+        );
+        assert_eq!(schema["properties"]["room"]["$ref"], "#/components/schemas/BoundedStr_8_255_Bytes_AllowAll");
+
+        let username = serde_json::to_value(<Username as utoipa::PartialSchema>::schema()).unwrap();
+        assert_eq!(
+            username,
+            json!({ "type": "string", "minLength": 3, "maxLength": 16, "pattern": "^[\\u0000-\\u007F]*$" })
+        );
+    }
+
+    #[test]
+    fn test_openapi_names_for_composed_policies() {
+        use bounded_str::{And, AsciiOnly, Charset, NoNul, Not, Or, charset};
+        type Both = BoundedStr<1, 8, 8, bounded_str::Bytes, And<AsciiOnly, NoNul>>;
+        type Either = BoundedStr<1, 8, 8, bounded_str::Bytes, Or<Not<AsciiOnly>, NoNul>>;
+        type Hex = BoundedStr<1, 8, 8, bounded_str::Bytes, Charset<{ charset("0123456789abcdef") }>>;
+        type Octal = BoundedStr<1, 8, 8, bounded_str::Bytes, Charset<{ charset("01234567") }>>;
+
+        let components = utoipa::openapi::ComponentsBuilder::new()
+            .schema_from::<Both>()
+            .schema_from::<Either>()
+            .schema_from::<Hex>()
+            .schema_from::<Octal>()
+            .build();
+        let names: Vec<&String> = components.schemas.keys().collect();
+        assert_eq!(names.len(), 4);
+        for name in &names {
+            assert!(name.bytes().all(|b| b.is_ascii_alphanumeric() || b == b'_'), "{name}");
+        }
+        assert!(components.schemas.contains_key("BoundedStr_1_8_Bytes_And_AsciiOnly_NoNul"));
+        assert!(components.schemas.contains_key("BoundedStr_1_8_Bytes_Or_Not_AsciiOnly_NoNul"));
+    }
+
+    #[test]
+    fn test_arbitrary_generates_valid_values() {
+        use arbitrary::{Arbitrary, Unstructured};
+        type Emoji = bounded_str::StackStr<2, 8, 32>;
+
+        let mut seed = 0x2545_f491_u32;
+        let data: Vec<u8> = (0..64 * 1024)
+            .map(|_| {
+                seed ^= seed << 13;
+                seed ^= seed >> 17;
+                seed ^= seed << 5;
+                seed as u8
+            })
+            .collect();
+        let mut u = Unstructured::new(&data);
+
+        for _ in 0..200 {
+            let name = Username::arbitrary(&mut u).expect("valid Username");
+            assert!((3..=16).contains(&name.chars().count()) && name.is_ascii());
+
+            let e = Emoji::arbitrary(&mut u).expect("valid Emoji");
+            assert!((2..=8).contains(&e.len()));
+        }
+    }
+
+    proptest::proptest! {
+        #[test]
+        fn test_proptest_values_stay_valid(name in proptest::prelude::any::<Username>()) {
+            proptest::prop_assert!((3..=16).contains(&name.chars().count()));
+            proptest::prop_assert!(name.is_ascii());
+        }
+    }
+
+    #[test]
+    fn test_proptest_hits_boundaries() {
+        use proptest::strategy::{Strategy, ValueTree};
+        use proptest::test_runner::TestRunner;
+
+        let strategy = Username::arbitrary_strategy();
+        let mut runner = TestRunner::deterministic();
+        let lens: Vec<usize> = (0..500)
+            .map(|_| strategy.new_tree(&mut runner).unwrap().current().chars().count())
+            .collect();
+        assert!(lens.contains(&3));
+        assert!(lens.contains(&16));
+    }
+
+    #[test]
+    fn test_quickcheck_values_and_shrinking() {
+        use quickcheck::{Arbitrary, QuickCheck};
+
+        fn in_bounds(name: Username) -> bool {
+            (3..=16).contains(&name.chars().count()) && name.is_ascii()
+        }
+        QuickCheck::new().quickcheck(in_bounds as fn(Username) -> bool);
+
+        let name = Username::new("Alexander").unwrap();
+        let shrunk: Vec<Username> = name.shrink().collect();
+        assert_eq!(shrunk[0], "Ale");
+        assert!(shrunk.iter().all(|s| s.len() >= 3 && s.len() < name.len()));
+        assert!(Username::new("Bob").unwrap().shrink().next().is_none());
+    }
+
+    #[test]
+    fn test_defmt_format_is_implemented() {
+        // Emitting needs a target-side global logger; check the bound only.
+        fn assert_format<T: defmt::Format>() {}
+        assert_format::<Username>();
+        assert_format::<bounded_str::FlexStr<0, 4096, 64>>();
+    }
+
+    #[test]
+    fn test_ufmt_matches_core_fmt() {
+        let name = BoundedStr::<1, 32, 32>::new("al\"i'c\te\u{301}").unwrap();
+        let secret = bounded_str::SecretBoundedStr::<1, 16>::new("hunter2").unwrap();
+        let mut out = String::new();
+        ufmt::uwrite!(out, "{} {:?}", name, name).unwrap();
+        assert_eq!(out, format!("{name} {name:?}"));
+        out.clear();
+        ufmt::uwrite!(out, "{} {:?}", secret, secret).unwrap();
+        assert_eq!(out, "***REDACTED*** BoundedStr(***REDACTED***)");
+        assert_eq!(out, format!("{secret} {secret:?}"));
+    }
+
+    #[test]
+    fn test_grapheme_length() {
+        type DisplayName = BoundedStr<1, 3, 64, bounded_str::Graphemes>;
+
+        // "e" + combining acute, a family ZWJ sequence and a flag: 3 graphemes, 9 chars.
+        let name = DisplayName::new("e\u{301}👨\u{200d}👩\u{200d}👧🇺🇦").unwrap();
+        assert_eq!(name.len_logical(), 3);
+        assert_eq!(name.chars().count(), 9);
+
+        let err = DisplayName::new("abcd").unwrap_err();
+        assert_eq!(err, BoundedStrError::TooLong { actual: 4, max: 3 });
+
+        let cut = DisplayName::new_truncate("e\u{301}e\u{301}e\u{301}e\u{301}").unwrap();
+        assert_eq!(cut.as_str(), "e\u{301}e\u{301}e\u{301}");
+    }
+
+    #[test]
+    fn test_display_width_length() {
+        type Cell = BoundedStr<1, 6, 32, bounded_str::DisplayWidth>;
+
+        let cell = Cell::new("日本語").unwrap();
+        assert_eq!(cell.len_logical(), 6);
+        assert_eq!(Cell::new("日本語!").unwrap_err(), BoundedStrError::TooLong { actual: 7, max: 6 });
+        assert_eq!(Cell::new("e\u{301}").unwrap().len_logical(), 1);
+        assert_eq!(Cell::new_truncate("ab日本語").unwrap().as_str(), "ab日本");
+    }
+
+    #[test]
+    fn test_utf16_length() {
+        type NVarChar = BoundedStr<1, 4, 16, bounded_str::Utf16Units>;
+
+        let s = NVarChar::new("a€😀").unwrap();
+        assert_eq!(s.len_logical(), 4);
+        assert_eq!(s.len_logical(), s.encode_utf16().count());
+        assert_eq!(NVarChar::new("ab😀!").unwrap_err().to_string(), "string too long (length 5, max 4)");
+        assert_eq!(NVarChar::new_truncate("abc😀").unwrap().as_str(), "abc");
+
+        const GREETING: NVarChar = bounded_str::bounded_str!(NVarChar, "hi😀");
+        assert_eq!(GREETING.len_logical(), 4);
+        assert!(NVarChar::check_const("😀😀😀").is_err());
+    }
+
+    #[test]
+    fn test_cached_logical_len() {
+        type Name = BoundedStr<0, 64, 8, bounded_str::Chars>;
+
+        let mut name = Name::new("Алёна").unwrap();
+        assert_eq!(name.len_logical(), 5);
+        name.try_push_str("-Марія").unwrap();
+        assert!(name.is_heap());
+        assert_eq!(name.len_logical(), 11);
+        name.mutate(|buf, len| *len -= buf[..*len].iter().rev().take(2).count()).unwrap();
+        assert_eq!(name.len_logical(), 10);
+        name.retain(|c| c != '-').unwrap();
+        assert_eq!(name.len_logical(), name.chars().count());
+        assert!(name.mutate(|_, len| *len = 1).is_err());
+        assert_eq!(name.len_logical(), 9);
+        name.clear().unwrap();
+        assert_eq!(name.len_logical(), 0);
+
+        // Only the boxed heap buffer carries the cache; inline values measure.
+        name.set("Ян").unwrap();
+        assert!(name.try_inline());
+        assert_eq!(name.len_logical(), 2);
+        name.reserve(64);
+        assert!(name.is_heap());
+        assert_eq!(name.len_logical(), 2);
+        #[cfg(target_pointer_width = "64")]
+        assert_eq!(std::mem::size_of::<Name>(), 16);
+    }
+
+    #[test]
+    #[cfg(target_pointer_width = "64")]
+    fn test_compact_stack_len() {
+        type Id = bounded_str::StackStr<1, 32>;
+
+        // 32 buffer bytes, a one-byte length and the variant tag, rounded up
+        // to the alignment of the boxed heap variant.
+        assert_eq!(std::mem::size_of::<Id>(), 40);
+        assert_eq!(std::mem::size_of::<bounded_str::StackStr<1, 8>>(), 16);
+        let ids: [Id; 4] = core::array::from_fn(|i| Id::new(&"x".repeat(i + 29)).unwrap());
+        assert_eq!(std::mem::size_of_val(&ids), 4 * 40);
+        assert_eq!(ids[3].len_bytes(), 32);
+        assert_eq!(ids[3].as_str(), "x".repeat(32));
+
+        // Past 126 bytes the length is kept as free space or, with at least
+        // 127 bytes free, in the unused tail of the buffer.
+        type Line = bounded_str::StackStr<0, 400>;
+        for n in [0, 126, 127, 200, 273, 274, 350, 400] {
+            let line = Line::new(&"y".repeat(n)).unwrap();
+            assert!(!line.is_heap());
+            assert_eq!(line.len_bytes(), n);
+            assert_eq!(line.clone().as_str(), "y".repeat(n));
+        }
+
+        // A rejected or panicking closure may clobber the tail; the length survives.
+        let mut line = Line::new(&"y".repeat(200)).unwrap();
+        assert!(line.mutate(|buf, len| { buf.fill(b'z'); *len = 401; }).is_err());
+        assert_eq!(line.as_str(), "y".repeat(200));
+        assert!(line.mutate(|buf, _| { buf.fill(0xFF); }).is_err());
+        assert_eq!(line.len_bytes(), 200);
+        let caught = std::panic::catch_unwind(std::panic::AssertUnwindSafe(|| {
+            let _ = line.mutate(|buf, _| { buf.fill(b'z'); panic!("boom") });
+        }));
+        assert!(caught.is_err());
+        assert_eq!(line.as_str(), "y".repeat(200));
+        line.mutate(|buf, len| { buf[200..].fill(b'z'); *len = 210; }).unwrap();
+        assert_eq!(line.as_str(), format!("{}{}", "y".repeat(200), "z".repeat(10)));
+        line.try_push_str("!").unwrap();
+        assert_eq!(line.len_bytes(), 211);
+    }
+
+    #[test]
+    fn test_option_niche() {
+        use std::mem::size_of;
+        type Tag = bounded_str::StackStr<1, 8>;
+        type Line = bounded_str::StackStr<0, 255>;
+        type Token = bounded_str::SecretBoundedStr<1, 64, 16>;
+
+        assert_eq!(size_of::<Option<Tag>>(), size_of::<Tag>());
+        assert_eq!(size_of::<Option<Line>>(), size_of::<Line>());
+        assert_eq!(size_of::<Option<Token>>(), size_of::<Token>());
+        assert_eq!(size_of::<Option<Username>>(), size_of::<Username>());
+
+        let empty: Option<Line> = Line::new("").ok();
+        assert_eq!(empty.as_deref(), Some(""));
+        let full: Option<Tag> = Tag::new("12345678").ok();
+        assert_eq!(full.map(|t| t.len_bytes()), Some(8));
+    }
+
+    #[test]
+    fn test_copy_str() {
+        type Label = bounded_str::CopyStr<1, 8, 8, bounded_str::Chars, bounded_str::AsciiOnly>;
+
+        let table = [Label::new("eu-west").unwrap(); 4];
+        let label = table[2];
+        assert_eq!(label, "eu-west");
+        assert_eq!(table[0], label);
+        assert_eq!(label.len_logical(), 7);
+        assert_eq!(format!("{label:>8}"), " eu-west");
+        assert_eq!(std::mem::size_of::<Label>(), 9);
+        assert_eq!(std::mem::size_of::<Option<Label>>(), 9);
+
+        assert!(matches!(Label::new(""), Err(BoundedStrError::TooShort { .. })));
+        assert!(matches!(Label::new("eu-central"), Err(BoundedStrError::TooLong { .. })));
+        assert!(matches!(Label::new("zürich"), Err(BoundedStrError::InvalidContent { .. })));
+        assert!(matches!(bounded_str::CopyStr::<0, 4, 4, bounded_str::Chars>::new("åäö"), Err(BoundedStrError::TooManyBytes { .. })));
+
+        let owned: BoundedStr<1, 8, 8, bounded_str::Chars, bounded_str::AsciiOnly> = label.into();
+        assert_eq!(owned.len_logical(), 7);
+        assert_eq!(Label::try_from(&owned).unwrap(), label);
+    }
+
+    #[test]
+    fn test_ffi_handle() {
+        use bounded_str::ffi::*;
+        use std::ptr;
+
+        unsafe {
+            let input = "héllo";
+            let mut handle = ptr::null_mut();
+            assert_eq!(bounded_str_new(input.as_ptr(), input.len(), 1, 16, &mut handle), BoundedStrStatus::Ok);
+            assert_eq!(bounded_str_len(handle), 6);
+            assert_eq!(std::slice::from_raw_parts(bounded_str_as_ptr(handle), bounded_str_len(handle)), input.as_bytes());
+            bounded_str_free(handle);
+
+            let mut untouched = ptr::null_mut();
+            assert_eq!(bounded_str_new(b"\xff".as_ptr(), 1, 0, 16, &mut untouched), BoundedStrStatus::InvalidUtf8);
+            assert_eq!(bounded_str_new(input.as_ptr(), input.len(), 8, 16, &mut untouched), BoundedStrStatus::TooShort);
+            assert_eq!(bounded_str_new(input.as_ptr(), input.len(), 0, 4, &mut untouched), BoundedStrStatus::TooLong);
+            assert_eq!(bounded_str_new(ptr::null(), 3, 0, 4, &mut untouched), BoundedStrStatus::NullPointer);
+            assert!(untouched.is_null());
+            assert_eq!(bounded_str_len(ptr::null()), 0);
+            bounded_str_free(ptr::null_mut());
+
+            // Typed values cross without revalidation.
+            let user = Username::new("alice").unwrap();
+            let handle = BoundedStrHandle::into_raw(user);
+            assert_eq!(BoundedStrHandle::as_str(handle), "alice");
+            assert_eq!(std::mem::size_of::<BoundedStrHandle>(), 0);
+            bounded_str_free(handle);
+        }
+    }
+
+    #[test]
+    fn test_bounded_c_str() {
+        use bounded_str::{BoundedCStr, NoNul};
+        use std::ffi::CStr;
+        type Ident = BoundedCStr<1, 8, 8>;
+
+        let ident = Ident::new("eth0").unwrap();
+        assert_eq!(ident.as_c_str(), c"eth0");
+        assert_eq!(ident.as_bytes_with_nul(), b"eth0\0");
+        assert_eq!(ident.len_bytes(), 4);
+        assert_eq!(unsafe { CStr::from_ptr(ident.as_ptr()) }, c"eth0");
+        assert_eq!(&*ident, "eth0");
+        assert!(!ident.is_heap());
+
+        assert!(matches!(Ident::new("a\0b"), Err(BoundedStrError::InvalidContent { byte_offset: 1, char: Some('\0') })));
+        assert!(matches!(Ident::new("123456789"), Err(BoundedStrError::TooLong { .. })));
+        assert!(matches!(Ident::try_from(c"\xff"), Err(BoundedStrError::InvalidUtf8)));
+        assert_eq!(Ident::try_from(c"lo").unwrap(), "lo");
+
+        // The terminator counts toward `MAX_BYTES`, so 8 bytes of content spill.
+        let full = Ident::new("12345678").unwrap();
+        assert!(full.is_heap());
+        assert_eq!(full.as_c_str(), c"12345678");
+
+        // Other format policies still reject interior NULs.
+        assert!(BoundedCStr::<0, 8, 8, bounded_str::Bytes, bounded_str::AsciiOnly>::new("a\0").is_err());
+        assert!(BoundedStr::<0, 8, 8, bounded_str::Bytes, NoNul>::new("a\0").is_err());
+        assert_eq!(ident.to_bounded::<8>().unwrap().as_str(), "eth0");
+    }
+
+    #[test]
+    fn test_from_reader() {
+        use std::io::{self, Read};
+        type Name = BoundedStr<1, 8, 8, bounded_str::Chars>;
+
+        fn kind(e: &io::Error) -> Option<BoundedStrError> {
+            e.get_ref().and_then(|e| e.downcast_ref::<BoundedStrError>()).copied()
+        }
+
+        // Feeds one byte per `read`, splitting multi-byte chars.
+        struct Trickle<'a>(&'a [u8]);
+        impl Read for Trickle<'_> {
+            fn read(&mut self, buf: &mut [u8]) -> io::Result<usize> {
+                let Some((&b, rest)) = self.0.split_first() else { return Ok(0) };
+                buf[0] = b;
+                self.0 = rest;
+                Ok(1)
+            }
+        }
+
+        assert_eq!(Name::from_reader("Žofie".as_bytes()).unwrap(), "Žofie");
+        assert_eq!(Name::from_reader(Trickle("日本語".as_bytes())).unwrap(), "日本語");
+
+        // An endless stream stops after `4 * MAX + 1` bytes.
+        let err = Name::from_reader(io::repeat(b'a')).unwrap_err();
+        assert_eq!(err.kind(), io::ErrorKind::InvalidData);
+        assert_eq!(kind(&err), Some(BoundedStrError::TooManyBytes { actual: 33, max_bytes: 32 }));
+
+        // Malformed input fails on the chunk that contains it.
+        let err = Name::from_reader(b"ab\xffcd".chain(io::repeat(b'a'))).unwrap_err();
+        assert_eq!(kind(&err), Some(BoundedStrError::InvalidUtf8));
+        assert_eq!(kind(&Name::from_reader(Trickle(b"ab\xe6\x97")).unwrap_err()), Some(BoundedStrError::InvalidUtf8));
+
+        assert!(matches!(kind(&Name::from_reader("ninechars".as_bytes()).unwrap_err()), Some(BoundedStrError::TooLong { .. })));
+        assert!(matches!(kind(&Name::from_reader(io::empty()).unwrap_err()), Some(BoundedStrError::TooShort { .. })));
+
+        // Grapheme clusters get four bytes per unit, so short clusters stream like `new()`.
+        type Label = BoundedStr<1, 4, 4, bounded_str::Graphemes>;
+        let accents = "e\u{301}".repeat(4);
+        assert_eq!(Label::from_reader(accents.as_bytes()).unwrap(), Label::new(&accents).unwrap());
+
+        // Longer clusters are a documented hard failure unless `MAX_BYTES` is raised.
+        let stacked = format!("a{}", "\u{301}".repeat(8));
+        assert!(Label::new(&stacked).is_ok());
+        assert_eq!(kind(&Label::from_reader(stacked.as_bytes()).unwrap_err()), Some(BoundedStrError::TooManyBytes { actual: 17, max_bytes: 16 }));
+        let mut builder = bounded_str::BoundedStrBuilder::<1, 4, 4, bounded_str::Graphemes>::new();
+        assert!(matches!(builder.push_str(&stacked), Err(BoundedStrError::TooManyBytes { max_bytes: 16, .. })));
+        type Wide = BoundedStr<1, 4, 64, bounded_str::Graphemes>;
+        assert_eq!(Wide::from_reader(stacked.as_bytes()).unwrap(), stacked.as_str());
+    }
+
+    #[test]
+    fn test_from_async_reader() {
+        use futures::{executor::block_on, io::{AllowStdIo, AsyncReadExt}};
+        type Name = BoundedStr<1, 8, 8, bounded_str::Chars>;
+
+        fn assert_send<T: Send>(_: &T) {}
+        let fut = Name::from_async_reader("Žofie".as_bytes());
+        assert_send(&fut);
+        assert_eq!(block_on(fut).unwrap(), "Žofie");
+
+        // An endless stream stops after `4 * MAX + 1` bytes.
+        let err = block_on(Name::from_async_reader(AllowStdIo::new(std::io::repeat(b'a')))).unwrap_err();
+        assert_eq!(err.kind(), std::io::ErrorKind::InvalidData);
+        let kind = err.get_ref().and_then(|e| e.downcast_ref::<BoundedStrError>()).copied();
+        assert_eq!(kind, Some(BoundedStrError::TooManyBytes { actual: 33, max_bytes: 32 }));
+
+        let split = "日本".as_bytes().chain("語".as_bytes());
+        assert_eq!(block_on(Name::from_async_reader(split)).unwrap(), "日本語");
+        assert!(block_on(Name::from_async_reader(&b"\xff"[..])).is_err());
+    }
+
+    #[test]
+    fn test_io_write_adapter() {
+        use std::io::{ErrorKind, Write};
+        type Line = BoundedStr<0, 8, 16, bounded_str::Chars>;
+
+        let mut line = Line::default();
+        let mut w = line.writer();
+        write!(w, "id={}", 42).unwrap();
+        let bytes = "ß€".as_bytes();
+        w.write_all(&bytes[..1]).unwrap();
+        w.write_all(&bytes[1..3]).unwrap();
+        w.write_all(&bytes[3..]).unwrap();
+        w.finish().unwrap();
+        assert_eq!(line, "id=42ß€");
+
+        // Each write is all-or-nothing; the value keeps what fit before.
+        let mut w = line.writer();
+        let err = w.write_all(b"xyz").unwrap_err();
+        assert_eq!(err.kind(), ErrorKind::WriteZero);
+        let kind = err.get_ref().and_then(|e| e.downcast_ref::<BoundedStrError>()).copied();
+        assert!(matches!(kind, Some(BoundedStrError::TooLong { actual: 10, max: 8 })));
+        assert_eq!(w.write(b"\xff").unwrap_err().kind(), ErrorKind::InvalidData);
+        w.write_all(b"!").unwrap();
+        w.write_all(&"€".as_bytes()[..2]).unwrap();
+        assert_eq!(w.finish().unwrap_err().kind(), ErrorKind::InvalidData);
+        assert_eq!(line, "id=42ß€!");
+
+        let mut ascii = BoundedStr::<0, 8, 8, bounded_str::Bytes, bounded_str::AsciiOnly>::default();
+        assert_eq!(ascii.writer().write(b"\xc3\xa9").unwrap_err().kind(), ErrorKind::InvalidData);
+    }
+
+    #[test]
+    fn test_split_bounded() {
+        use bounded_str::BoundedStrRef;
+        type Field = BoundedStr<1, 8, 8, bounded_str::Chars, bounded_str::AsciiOnly>;
+        type FieldRef<'a> = BoundedStrRef<'a, 1, 8, bounded_str::Chars, bounded_str::AsciiOnly>;
+
+        let fields: Vec<Field> = Field::split_bounded("host:port:user", ":").collect::<Result<_, _>>().unwrap();
+        assert_eq!(fields, ["host", "port", "user"]);
+
+        let line = String::from("db, 5432, admin");
+        let refs: Vec<FieldRef<'_>> = FieldRef::split_bounded(&line, ", ").collect::<Result<_, _>>().unwrap();
+        assert_eq!(refs[1], "5432");
+        assert_eq!(refs[2].as_str().as_ptr(), line[10..].as_ptr());
+
+        // Bad fields are reported in place; the rest still come through.
+        let res: Vec<_> = Field::split_bounded("ok::toolongfield:ünï", ":").collect();
+        assert!(res[0].is_ok());
+        assert!(matches!(res[1], Err(BoundedStrError::TooShort { .. })));
+        assert!(matches!(res[2], Err(BoundedStrError::TooLong { .. })));
+        assert!(matches!(res[3], Err(BoundedStrError::InvalidContent { .. })));
+
+        let lines: Vec<_> = FieldRef::lines_bounded("alpha\r\nbeta\ngamma\n").map(Result::unwrap).collect();
+        assert_eq!(lines, ["alpha", "beta", "gamma"]);
+        assert!(Field::lines_bounded("a\n\nb").any(|l| l.is_err()));
+    }
+
+    #[test]
+    fn test_chunks_of() {
+        type Piece = BoundedStr<1, 4, 16, bounded_str::Chars>;
+
+        let pieces: Vec<_> = Piece::chunks_of("Grüße, Welt!").map(Result::unwrap).collect();
+        assert_eq!(pieces.iter().map(|p| p.as_str()).collect::<Vec<_>>(), ["Grüß", "e, W", "elt!"]);
+        assert_eq!(Piece::chunks_of("").count(), 0);
+        assert_eq!(Piece::chunks_of("abcde").map(|p| p.unwrap().len_logical()).collect::<Vec<_>>(), [4, 1]);
+
+        // Graphemes stay whole.
+        type Glyphs = BoundedStr<1, 2, 32, bounded_str::Graphemes>;
+        let pieces: Vec<_> = Glyphs::chunks_of("e\u{301}a👍🏽b").map(Result::unwrap).collect();
+        assert_eq!(pieces.iter().map(|p| p.as_str()).collect::<Vec<_>>(), ["e\u{301}a", "👍🏽b"]);
+
+        // Iteration stops at the first piece that fails.
+        type Ascii = BoundedStr<2, 2, 2, bounded_str::Bytes, bounded_str::AsciiOnly>;
+        let res: Vec<_> = Ascii::chunks_of("abcdé-fg").collect();
+        assert_eq!(res.len(), 3);
+        assert!(matches!(res[2], Err(BoundedStrError::InvalidContent { .. })));
+        let res: Vec<_> = Ascii::chunks_of("abc").collect();
+        assert!(matches!(res[..], [Ok(_), Err(BoundedStrError::TooShort { .. })]));
+    }
+
+    #[test]
+    fn test_streaming_builder() {
+        use bounded_str::BoundedStrBuilder;
+        type Name = BoundedStr<1, 8, 8, bounded_str::Chars, bounded_str::AsciiOnly>;
+        type Text = BoundedStr<0, 4, 16, bounded_str::Utf16Units>;
+
+        let mut b = BoundedStrBuilder::<1, 8, 8, bounded_str::Chars, bounded_str::AsciiOnly>::new();
+        b.push_str("ali").unwrap().push_chunk(b"ce").unwrap();
+        assert_eq!(b.len_logical(), 5);
+        let name: Name = b.finish().unwrap();
+        assert_eq!(name, "alice");
+
+        // A char split across chunks counts once both halves arrive.
+        let mut b = BoundedStrBuilder::<0, 4, 16, bounded_str::Utf16Units>::new();
+        let bytes = "a😀b".as_bytes();
+        b.push_chunk(&bytes[..3]).unwrap();
+        assert_eq!((b.len_bytes(), b.len_logical()), (3, 1));
+        b.push_chunk(&bytes[3..]).unwrap();
+        assert_eq!(b.len_logical(), 4);
+        let err = b.push_str("c").unwrap_err();
+        assert_eq!(err, BoundedStrError::TooLong { actual: 5, max: 4 });
+        let text: Text = b.finish().unwrap();
+        assert_eq!(text, "a😀b");
+
+        // Rejected chunks leave the builder as it was.
+        let mut b = BoundedStrBuilder::<1, 8, 8, bounded_str::Chars, bounded_str::AsciiOnly>::new();
+        b.push_str("ab").unwrap();
+        assert_eq!(b.push_chunk(b"\xffc").unwrap_err(), BoundedStrError::InvalidUtf8);
+        assert_eq!(b.len_bytes(), 2);
+        b.push_chunk(&"é".as_bytes()[..1]).unwrap();
+        assert!(matches!(b.finish(), Err(BoundedStrError::InvalidUtf8)));
+
+        // The format policy runs in `finish()`.
+        let mut b = BoundedStrBuilder::<1, 8, 8, bounded_str::Chars, bounded_str::AsciiOnly>::new();
+        b.push_str("é").unwrap();
+        assert!(matches!(b.finish(), Err(BoundedStrError::InvalidContent { .. })));
+        assert!(matches!(BoundedStrBuilder::<1, 8, 8>::new().finish(), Err(BoundedStrError::TooShort { .. })));
+        assert_eq!(BoundedStrBuilder::<0, 4, 4>::new().push_str("12345").unwrap_err(), BoundedStrError::TooLong { actual: 5, max: 4 });
+    }
+
+    #[test]
+    fn test_auto_traits() {
+        use std::panic::{RefUnwindSafe, UnwindSafe};
+        use std::{marker::PhantomData, rc::Rc};
+
+        // A policy that is neither `Send` nor `Sync` itself.
+        struct LocalPolicy(PhantomData<Rc<()>>);
+        impl bounded_str::FormatPolicy for LocalPolicy {
+            fn check(s: &str) -> bool { !s.is_empty() }
+        }
+
+        fn assert_all<T: Send + Sync + UnwindSafe + RefUnwindSafe>() {}
+        assert_all::<BoundedStr<0, 16, 16, bounded_str::Bytes, LocalPolicy>>();
+        assert_all::<bounded_str::SecretBoundedStr<0, 16, 16>>();
+        assert_all::<bounded_str::BoundedStrRef<'static, 0, 16, bounded_str::Chars, LocalPolicy>>();
+        assert_all::<bounded_str::BoundedCow<'static, 0, 16, 16, bounded_str::Bytes, LocalPolicy>>();
+        assert_all::<bounded_str::SharedBoundedStr<0, 16, bounded_str::Bytes, LocalPolicy>>();
+        assert_all::<bounded_str::BoundedStrIn<0, 16, bounded_str::Bytes, LocalPolicy>>();
+        assert_all::<bounded_str::CopyStr<0, 16, 16, bounded_str::Bytes, LocalPolicy>>();
+        assert_all::<bounded_str::BoundedCStr<0, 16, 16, bounded_str::Bytes, LocalPolicy>>();
+
+        let name = BoundedStr::<0, 16, 16, bounded_str::Bytes, LocalPolicy>::new("worker").unwrap();
+        let joined = std::thread::spawn(move || name.len_bytes()).join().unwrap();
+        assert_eq!(joined, 6);
+    }
+
+    #[test]
+    fn test_truncate_with_ellipsis() {
+        type Title = BoundedStr<1, 64, 64, bounded_str::Chars>;
+
+        let title = Title::new("Привет, мир!").unwrap();
+        assert_eq!(title.truncate_with_ellipsis(8, "…").unwrap(), "Привет,…");
+        assert_eq!(title.truncate_with_ellipsis(8, "...").unwrap(), "Приве...");
+        assert_eq!(title.truncate_with_ellipsis(12, "…").unwrap(), title);
+        assert_eq!(title.truncate_with_ellipsis(1, "...").unwrap(), "...");
+
+        // Grapheme clusters are never split.
+        type Label = BoundedStr<1, 16, 64, bounded_str::Graphemes>;
+        let label = Label::new("ne\u{301}e\u{301}ne\u{301}").unwrap();
+        assert_eq!(label.truncate_with_ellipsis(3, "…").unwrap(), "ne\u{301}…");
+
+        // Without room for content, `MIN` can still reject the result.
+        type Code = BoundedStr<4, 8, 8>;
+        assert!(matches!(Code::new("ABCDEFGH").unwrap().truncate_with_ellipsis(3, "…"), Err(BoundedStrError::TooShort { .. })));
+    }
+
+    #[test]
+    fn test_control_char_policies() {
+        use bounded_str::{NoControlChars, PrintableAscii};
+
+        type LogField = BoundedStr<1, 64, 64, bounded_str::Chars, NoControlChars>;
+        assert!(LogField::new("user=ключ id=42").is_ok());
+        assert!(matches!(LogField::new("ok\nFAKE ENTRY"), Err(BoundedStrError::InvalidContent { byte_offset: 2, char: Some('\n') })));
+        assert!(matches!(LogField::new("a\u{9B}31m"), Err(BoundedStrError::InvalidContent { byte_offset: 1, .. })));
+        assert!(LogField::new("a\x7F").is_err());
+        assert!(BoundedStr::<1, 64, 64, bounded_str::Chars, NoControlChars<false>>::new("a\x7F").is_ok());
+
+        type HeaderValue = BoundedStr<1, 64, 64, bounded_str::Bytes, PrintableAscii>;
+        assert!(HeaderValue::new("Bearer abc.DEF~123").is_ok());
+        assert!(matches!(HeaderValue::new("abc\tdef"), Err(BoundedStrError::InvalidContent { byte_offset: 3, .. })));
+        assert!(matches!(HeaderValue::new("caf\u{e9}"), Err(BoundedStrError::InvalidContent { byte_offset: 3, .. })));
+        assert!(HeaderValue::new("~\x7F").is_err());
+
+        // Printable ASCII is also free of control chars.
+        let v = HeaderValue::new("X-Trace").unwrap();
+        let relaxed: BoundedStr<1, 64, 64, bounded_str::Bytes, NoControlChars> = v.relax();
+        assert_eq!(relaxed, "X-Trace");
+    }
+
+    #[test]
+    fn test_alphanumeric_and_identifier_policies() {
+        use bounded_str::{AsciiAlphanumeric, Identifier};
+
+        type ApiToken = BoundedStr<1, 128, 128, bounded_str::Chars, AsciiAlphanumeric>;
+        assert!(ApiToken::new("a1b2c3d4e5").is_ok());
+        assert!(matches!(ApiToken::new("a1-b2"), Err(BoundedStrError::InvalidContent { byte_offset: 2, char: Some('-') })));
+        assert!(matches!(ApiToken::new("a1ü"), Err(BoundedStrError::InvalidContent { byte_offset: 2, char: Some('ü') })));
+        // Agrees with the hand-written `TokenPolicy`.
+        for s in ["abc", "ABC123", "a b", "tok_en", "ключ"] {
+            assert_eq!(ApiToken::new(s).is_ok(), Token::new(s).is_ok(), "{s}");
+        }
+
+        type Ident = BoundedStr<1, 32, 32, bounded_str::Chars, Identifier>;
+        for ok in ["x", "_private", "snake_case_1", "CamelCase"] {
+            assert!(Ident::new(ok).is_ok(), "{ok}");
+        }
+        assert!(matches!(Ident::new("1st"), Err(BoundedStrError::InvalidContent { byte_offset: 0, .. })));
+        assert!(matches!(Ident::new("max-len"), Err(BoundedStrError::InvalidContent { byte_offset: 3, .. })));
+        assert!(matches!(Ident::new("имя"), Err(BoundedStrError::InvalidContent { byte_offset: 0, .. })));
+        assert!(matches!(Ident::new(""), Err(BoundedStrError::TooShort { .. })));
+        assert_eq!(Ident::new("field_name").unwrap().len_logical(), 10);
+    }
+
+    #[test]
+    fn test_charset_policy() {
+        use bounded_str::{charset, Charset};
+
+        type Hex = BoundedStr<2, 64, 64, bounded_str::Bytes, Charset<{ charset("0123456789abcdef") }>>;
+        type DialPad = BoundedStr<1, 15, 15, bounded_str::Chars, Charset<{ charset("0123456789*#+") }>>;
+
+        assert!(Hex::new("deadbeef").is_ok());
+        assert!(matches!(Hex::new("deadBEEF"), Err(BoundedStrError::InvalidContent { byte_offset: 4, char: Some('B') })));
+        assert!(matches!(Hex::new("00ü"), Err(BoundedStrError::InvalidContent { byte_offset: 2, char: Some('ü') })));
+        assert!(DialPad::new("+1*555#").is_ok());
+        assert!(DialPad::new("555-1234").is_err());
+
+        // The mask is known at compile time, so literals are checked there too.
+        const KEY: Hex = bounded_str::bounded_str!(Hex, "c0ffee");
+        assert_eq!(KEY, "c0ffee");
+        assert!(Hex::check_const("c0ffee!").is_err());
+
+        // Combinators intersect or unite the sets.
+        type Upper = Charset<{ charset("ABCDEFGHIJKLMNOPQRSTUVWXYZ234567") }>;
+        type Base32 = BoundedStr<1, 16, 16, bounded_str::Bytes, bounded_str::And<Upper, bounded_str::AsciiOnly>>;
+        const CODE: Base32 = bounded_str::bounded_str!(Base32, "MZXW6YQ");
+        assert_eq!(CODE, "MZXW6YQ");
+    }
+
+    #[test]
+    fn test_regex_policy() {
+        use bounded_str::{FormatPolicy, RegexPattern, RegexPolicy};
+
+        bounded_str::regex_policy!(Sku = r"[A-Z]{3}-[0-9]{4}");
+        type SkuStr = BoundedStr<8, 8, 8, bounded_str::Bytes, RegexPolicy<Sku>>;
+
+        assert!(SkuStr::new("ABC-1234").is_ok());
+        // The pattern is anchored: a match inside the string is not enough.
+        assert!(matches!(SkuStr::new("xABC-123"), Err(BoundedStrError::InvalidContent { byte_offset: 0, char: None })));
+        assert!(matches!(SkuStr::new("ABC-12345"), Err(BoundedStrError::TooLong { .. })));
+        assert_eq!(Sku::PATTERN, "^(?:[A-Z]{3}-[0-9]{4})$");
+        assert_eq!(RegexPolicy::<Sku>::json_pattern(), Some(Sku::PATTERN));
+        assert!(std::ptr::eq(Sku::regex(), Sku::regex()));
+
+        // Alternation stays inside the anchors.
+        bounded_str::regex_policy!(Env = "dev|staging|prod");
+        type EnvStr = BoundedStr<1, 16, 16, bounded_str::Bytes, bounded_str::And<bounded_str::AsciiOnly, RegexPolicy<Env>>>;
+        assert!(EnvStr::new("prod").is_ok());
+        assert!(EnvStr::new("devprod").is_err());
+    }
+
+    #[test]
+    fn test_policy_combinators() {
+        use bounded_str::{And, AsciiOnly, FormatPolicy, Not, Or};
+
+        struct NoWhitespace;
+        impl FormatPolicy for NoWhitespace {
+            fn check(s: &str) -> bool { !s.contains(char::is_whitespace) }
+            fn invalid_at(s: &str) -> Option<usize> { s.find(char::is_whitespace) }
+        }
+        struct Digits;
+        impl FormatPolicy for Digits {
+            fn check(s: &str) -> bool { s.bytes().all(|b| b.is_ascii_digit()) }
+        }
+
+        type Handle = BoundedStr<3, 16, 16, bounded_str::Chars, And<AsciiOnly, NoWhitespace>>;
+        assert!(Handle::new("alice_01").is_ok());
+        assert!(matches!(Handle::new("ali ce"), Err(BoundedStrError::InvalidContent { byte_offset: 3, .. })));
+        assert!(matches!(Handle::new("ali ü"), Err(BoundedStrError::InvalidContent { byte_offset: 3, .. })));
+        assert!(matches!(Handle::new("alü ce"), Err(BoundedStrError::InvalidContent { byte_offset: 2, .. })));
+        // Length errors still come first.
+        assert!(matches!(Handle::new("ü"), Err(BoundedStrError::TooShort { .. })));
+
+        type Pin = BoundedStr<4, 8, 8, bounded_str::Bytes, Or<Digits, Not<AsciiOnly>>>;
+        assert!(Pin::new("1234").is_ok());
+        assert!(Pin::new("ключ").is_ok());
+        assert!(matches!(Pin::new("12a4"), Err(BoundedStrError::InvalidContent { .. })));
+
+        // Built-in parts keep compile-time checking.
+        type DeviceId = BoundedStr<1, 8, 8, bounded_str::Bytes, And<AsciiOnly, bounded_str::AllowAll>>;
+        const ID: DeviceId = bounded_str::bounded_str!(DeviceId, "dev-01");
+        assert_eq!(ID, "dev-01");
+    }
+
+    #[test]
+    fn test_pad_to_min() {
+        type Field = BoundedStr<8, 8, 8>;
+        type Name = BoundedStr<1, 8, 8>;
+
+        assert_eq!(Field::new_padded("JOHN", ' ', bounded_str::PadSide::Right).unwrap(), "JOHN    ");
+        assert_eq!(Field::new_padded("42", '0', bounded_str::PadSide::Left).unwrap(), "00000042");
+        assert_eq!(Field::new_padded("ABC", '*', bounded_str::PadSide::Center).unwrap(), "**ABC***");
+        assert_eq!(Field::new_padded("ABCDEFGH", ' ', bounded_str::PadSide::Right).unwrap(), "ABCDEFGH");
+        assert!(matches!(Field::new_padded("ABCDEFGHI", ' ', bounded_str::PadSide::Right), Err(BoundedStrError::TooLong { .. })));
+
+        let name = Name::new("SMITH").unwrap();
+        let field: Field = name.pad_to_min(' ', bounded_str::PadSide::Right).unwrap();
+        assert_eq!(field, "SMITH   ");
+
+        // A multi-byte fill that can't land exactly on `MIN` bytes overshoots `MAX`.
+        assert!(matches!(Field::new_padded("ABC", 'ü', bounded_str::PadSide::Right), Err(BoundedStrError::TooLong { .. })));
+        type Chars8 = BoundedStr<8, 8, 32, bounded_str::Chars>;
+        assert_eq!(Chars8::new_padded("ABC", 'ü', bounded_str::PadSide::Left).unwrap(), "üüüüüABC");
+
+        // Padding still goes through the format policy.
+        type Ascii8 = BoundedStr<8, 8, 32, bounded_str::Chars, bounded_str::AsciiOnly>;
+        assert!(matches!(Ascii8::new_padded("ABC", 'ü', bounded_str::PadSide::Left), Err(BoundedStrError::InvalidContent { .. })));
+    }
+
+    #[test]
+    fn test_split_off_and_drain() {
+        type Frame = BoundedStr<2, 32, 32, bounded_str::Chars>;
+        type Header = BoundedStr<4, 4, 4, bounded_str::Chars>;
+
+        let mut frame = Frame::new("HDR1payload-ü").unwrap();
+        let header: Header = frame.drain(0..4).unwrap();
+        assert_eq!(header, "HDR1");
+        assert_eq!(frame, "payload-ü");
+        assert_eq!(frame.len_logical(), 9);
+
+        let mut tail = frame.try_split_off(7).unwrap();
+        assert_eq!((frame.as_str(), tail.as_str()), ("payload", "-ü"));
+
+        // Failures leave the value untouched.
+        assert_eq!(frame.try_split_off(6).unwrap_err(), BoundedStrError::TooShort { actual: 1, min: 2 });
+        assert_eq!(frame.try_split_off(1).unwrap_err(), BoundedStrError::TooShort { actual: 1, min: 2 });
+        assert_eq!(frame.try_split_off(99).unwrap_err(), BoundedStrError::NotCharBoundary);
+        assert!(matches!(frame.drain::<4, 4, 4>(0..3), Err(BoundedStrError::TooShort { .. })));
+        assert!(matches!(frame.drain::<4, 4, 4>(0..6), Err(BoundedStrError::TooLong { .. })));
+        assert!(matches!(frame.drain::<0, 8, 8>(1..7), Err(BoundedStrError::TooShort { .. })));
+        assert_eq!(frame, "payload");
+        assert_eq!(tail.drain::<1, 1, 1>(1..2).unwrap_err(), BoundedStrError::NotCharBoundary);
+    }
+
+    #[test]
+    fn test_set_in_place() {
+        let mut user = Username::new("alice").unwrap();
+        user.set("bob").unwrap();
+        assert_eq!(user.as_str(), "bob");
+        assert_eq!(user.len_logical(), 3);
+
+        // Rejected values leave the old content untouched.
+        assert!(matches!(user.set("al"), Err(BoundedStrError::TooShort { .. })));
+        assert!(matches!(user.set("ÿÿÿÿ"), Err(BoundedStrError::InvalidContent { .. })));
+        assert_eq!(user.as_str(), "bob");
+
+        type Doc = bounded_str::FlexStr<0, 10_000, 16>;
+        let mut doc = Doc::new(&"x".repeat(4000)).unwrap();
+        let ptr = doc.as_ptr();
+        doc.set(&"y".repeat(3000)).unwrap();
+        assert_eq!(doc.as_ptr(), ptr);
+        assert_eq!(doc.len_logical(), 3000);
+        doc.set("tiny").unwrap();
+        assert_eq!(doc.as_ptr(), ptr);
+        assert_eq!(doc.as_str(), "tiny");
+    }
+
+    #[test]
+    fn test_bounded_str_ref() {
+        use bounded_str::{AsciiOnly, BoundedStrRef, Chars};
+        type NameRef<'a> = BoundedStrRef<'a, 3, 16, Chars, AsciiOnly>;
+
+        let input = String::from("alice,bo,ÿÿÿÿ");
+        let fields: Vec<_> = input.split(',').map(NameRef::new).collect();
+        let alice = *fields[0].as_ref().unwrap();
+        assert_eq!(alice.as_str().as_ptr(), input.as_ptr());
+        assert_eq!(alice.len_logical(), 5);
+        assert!(matches!(fields[1], Err(BoundedStrError::TooShort { .. })));
+        assert!(matches!(fields[2], Err(BoundedStrError::InvalidContent { .. })));
+
+        let owned: Username = alice.to_bounded().unwrap();
+        assert_eq!(owned, "alice");
+        assert_eq!(owned.as_bounded_ref(), alice);
+        assert_eq!(format!("{alice}"), "alice");
+    }
+
+    #[test]
+    fn test_bounded_cow() {
+        use bounded_str::{AsciiOnly, BoundedCow, Chars};
+        type NameCow<'a> = BoundedCow<'a, 3, 16, 32, Chars, AsciiOnly>;
+
+        let input = String::from("alice");
+        let mut name = NameCow::new(&input).unwrap();
+        assert!(name.is_borrowed());
+        assert_eq!(name.as_ptr(), input.as_ptr());
+        assert!(matches!(NameCow::new("al"), Err(BoundedStrError::TooShort { .. })));
+
+        // A rejected edit still copies, but leaves the content alone.
+        assert!(name.mutate(|buf, _| buf[0] = 0xFF).is_err());
+        assert!(!name.is_borrowed());
+        assert_eq!(name, "alice");
+
+        name.to_mut().unwrap().try_push_str("_b").unwrap();
+        assert_eq!(name.len_logical(), 7);
+        let owned: Username = name.into_owned().unwrap();
+        assert_eq!(owned, "alice_b");
+
+        let passthrough = NameCow::new(&input).unwrap();
+        assert_eq!(passthrough.into_owned().unwrap(), "alice");
+    }
+
+    #[test]
+    fn test_heapless_interop() {
+        let raw: heapless::String<32> = heapless::String::try_from("alice").unwrap();
+        let user = Username::try_from(&raw).unwrap();
+        assert_eq!(user, "alice");
+
+        let short: heapless::String<8, u8> = heapless::String::try_from("al").unwrap();
+        assert!(matches!(Username::try_from(&short), Err(BoundedStrError::TooShort { .. })));
+
+        let back: heapless::String<16> = user.to_heapless().unwrap();
+        assert_eq!(back.as_str(), "alice");
+        assert!(matches!(user.to_heapless::<4>(), Err(BoundedStrError::TooManyBytes { actual: 5, max_bytes: 4 })));
+    }
+
+    #[test]
+    fn test_arrayvec_interop() {
+        use arrayvec::ArrayString;
+
+        let raw = ArrayString::<16>::from("alice").unwrap();
+        assert_eq!(Username::try_from(&raw).unwrap(), "alice");
+        let bad = ArrayString::<16>::from("al!ce ÿ").unwrap();
+        assert!(Username::try_from(&bad).is_err());
+
+        // Every `ArrayString<16>` fits, so this direction can't fail.
+        let note: bounded_str::StackStr<0, 16> = ArrayString::<16>::from("hi there").unwrap().into();
+        assert_eq!(note.len_logical(), 8);
+        let chars: BoundedStr<0, 16, 16, bounded_str::Chars> = ArrayString::<16>::from("ÿÿ").unwrap().into();
+        assert_eq!(chars.len_logical(), 2);
+
+        let back = ArrayString::<8>::try_from(&note).unwrap();
+        assert_eq!(back.as_str(), "hi there");
+        assert!(matches!(ArrayString::<4>::try_from(&note), Err(BoundedStrError::TooManyBytes { actual: 8, max_bytes: 4 })));
+    }
+
+    #[test]
+    fn test_small_string_interop() {
+        use compact_str::CompactString;
+        use smol_str::SmolStr;
+        type Body = bounded_str::FlexStr<1, 4096, 16>;
+
+        let user = Username::try_from(CompactString::new("alice")).unwrap();
+        assert!(Username::try_from(CompactString::new("al")).is_err());
+        assert_eq!(CompactString::from(user), "alice");
+
+        // Heap buffers move across without reallocating.
+        let big = CompactString::from("x".repeat(100));
+        let ptr = big.as_ptr();
+        let body = Body::try_from(big).unwrap();
+        assert!(body.is_heap());
+        assert_eq!(body.as_ptr(), ptr);
+        let back = CompactString::from(body);
+        assert_eq!(back.as_ptr(), ptr);
+
+        let smol = SmolStr::new("bob_smith");
+        let user = Username::try_from(&smol).unwrap();
+        assert_eq!(SmolStr::from(&user), smol);
+        assert!(Username::try_from(&SmolStr::new("bob_ÿ")).is_err());
+    }
+
+    #[test]
+    fn test_fused_validation() {
+        use bounded_str::{FormatPolicy, LengthPolicy, Violation};
+
+        // Counts chars and checks them in the same loop.
+        struct Digits;
+        impl FormatPolicy for Digits {
+            fn check(s: &str) -> bool { s.bytes().all(|b| b.is_ascii_digit()) }
+
+            fn validate<L: LengthPolicy>(s: &str) -> Result<usize, Violation> {
+                let (mut n, mut bad) = (0, None);
+                for (i, c) in s.char_indices() {
+                    n += 1;
+                    if bad.is_none() && !c.is_ascii_digit() { bad = Some(i); }
+                }
+                match bad {
+                    None => Ok(n),
+                    Some(i) => Err(Violation { logical_len: n, byte_offset: Some(i) }),
+                }
+            }
+        }
+        type Pin = BoundedStr<4, 6, 8, bounded_str::Chars, Digits>;
+
+        assert_eq!(Pin::new("1234").unwrap().len_logical(), 4);
+        assert_eq!(Pin::new("12a4").unwrap_err(), BoundedStrError::InvalidContent { byte_offset: 2, char: Some('a') });
+        // Length errors still win over format errors.
+        assert_eq!(Pin::new("1234567x").unwrap_err(), BoundedStrError::TooLong { actual: 8, max: 6 });
+        let mut pin = Pin::new("1234").unwrap();
+        assert_eq!(pin.mutate(|buf, _| buf[0] = b'x').unwrap_err(),
+            BoundedStrError::MutationFailed { kind: MutationErrorKind::PolicyViolation });
+
+        // The built-in fast path for AsciiOnly reports the same errors as before.
+        assert_eq!(Username::new("Алёна-1").unwrap_err(), BoundedStrError::InvalidContent { byte_offset: 0, char: Some('А') });
+        assert_eq!(Username::new("Ал").unwrap_err(), BoundedStrError::TooShort { actual: 2, min: 3 });
+    }
+
+    #[test]
+    fn test_simd_validation_large_body() {
+        type Body = BoundedStr<0, 65536, 64, bounded_str::Chars>;
+
+        let text = "Matrix ✓ ключ 🔑 ".repeat(1000);
+        let body = Body::new(&text).unwrap();
+        assert_eq!(body.len_logical(), text.chars().count());
+
+        let mut bytes = text.clone().into_bytes();
+        assert_eq!(Body::from_utf8(&bytes).unwrap().as_str(), text);
+        bytes[5000] = 0xFF;
+        assert_eq!(Body::from_utf8(&bytes).unwrap_err(), BoundedStrError::InvalidUtf8);
+        assert!(Body::from_utf8_lossy(&bytes).unwrap().contains('\u{FFFD}'));
+    }
+
+    #[test]
+    fn test_mutate_stack_rolls_back() {
+        type Page = bounded_str::StackStr<1, 4096>;
+
+        let mut page = Page::new("hello world").unwrap();
+        page.mutate(|buf, len| { buf[*len..*len + 1].copy_from_slice(b"!"); *len += 1; }).unwrap();
+        assert_eq!(page.as_str(), "hello world!");
+
+        assert!(page.mutate(|buf, len| { buf[0] = 0xFF; *len = 3; }).is_err());
+        assert!(page.mutate(|buf, len| { buf[0] = b'j'; *len = 4097; }).is_err());
+        assert_eq!(page.as_str(), "hello world!");
+        assert_eq!(page.len_logical(), 12);
+    }
+
+    #[test]
+    fn test_mutate_in_place_unchecked() {
+        type Tag = bounded_str::StackStr<1, 8>;
+        type Body = bounded_str::FlexStr<1, 64, 4, bounded_str::Chars>;
+
+        let mut tag = Tag::new("abc").unwrap();
+        // SAFETY: ASCII upper-casing keeps the length and stays valid UTF-8.
+        unsafe { tag.mutate_in_place_unchecked(|buf, len| buf[..*len].make_ascii_uppercase()) };
+        assert_eq!(tag.as_str(), "ABC");
+
+        let mut body = Body::new("привет").unwrap();
+        // SAFETY: appending ASCII keeps UTF-8 valid and stays within 64 chars.
+        let appended = unsafe {
+            body.mutate_in_place_unchecked(|buf, len| {
+                buf[*len..*len + 2].copy_from_slice(b"!!");
+                *len += 2;
+                2
+            })
+        };
+        assert_eq!(appended, 2);
+        assert_eq!(body.as_str(), "привет!!");
+        assert_eq!(body.len_logical(), 8);
+    }
+
+    #[test]
+    fn test_large_stack_buffer_prefix_only() {
+        type Page = bounded_str::StackStr<0, 8192>;
+
+        let page = Page::new("header").unwrap();
+        let mut copy = page.clone();
+        assert_eq!(copy, page);
+        copy.try_push_str("+body").unwrap();
+        copy.try_remove_range(0..7).unwrap();
+        assert_eq!(copy.as_str(), "body");
+
+        // The unused tail handed to `mutate` reads as zeros.
+        copy.mutate(|buf, len| assert!(buf[*len..].iter().all(|&b| b == 0))).unwrap();
+        assert_eq!(Page::default().as_str(), "");
     }
 
     #[test]
@@ -208,7 +1505,7 @@ mod tests {
 
         let parsed: InputData = serde_json::from_str(&json_data).expect("JSON should parse");
         let err = Username::new(&parsed.username).unwrap_err();
-        assert!(matches!(err, BoundedStrError::TooShort));
+        assert!(matches!(err, BoundedStrError::TooShort { .. }));
     }
 
     #[test]
@@ -221,7 +1518,7 @@ mod tests {
 
         let parsed: InputData = serde_json::from_str(&json_data).expect("JSON should parse");
         let err = Token::new(&parsed.token).unwrap_err();
-        assert!(matches!(err, BoundedStrError::InvalidContent));
+        assert!(matches!(err, BoundedStrError::InvalidContent { .. }));
     }
 }
 
@@ -263,7 +1560,7 @@ mod heap_tests {
     fn heap_too_long_error() {
         let too_long = "C".repeat(65537);
         let err = HtmlBody::new(&too_long).unwrap_err();
-        assert!(matches!(err, BoundedStrError::TooLong | BoundedStrError::TooManyBytes));
+        assert!(matches!(err, BoundedStrError::TooLong { .. } | BoundedStrError::TooManyBytes { .. }));
     }
 
     #[test]
@@ -275,7 +1572,54 @@ mod heap_tests {
         assert_eq!(t.as_str(), valid_token);
 
         let err = BigToken::new(invalid_token).unwrap_err();
-        assert!(matches!(err, BoundedStrError::InvalidContent));
+        assert!(matches!(err, BoundedStrError::InvalidContent { .. }));
+    }
+
+    #[test]
+    fn heap_try_from_string_reuses_buffer() {
+        let large = "A".repeat(5000);
+        let ptr = large.as_ptr();
+        let b = HtmlBody::try_from(large).expect("Large string should take over the buffer");
+        assert_eq!(b.len_bytes(), 5000);
+        assert_eq!(b.as_ptr(), ptr);
+
+        let small = HtmlBody::try_from(String::from("short")).expect("Small string goes to stack");
+        assert_eq!(small.as_str(), "short");
+
+        let err = BigToken::try_from("🔥".repeat(200)).unwrap_err();
+        assert!(matches!(err, BoundedStrError::InvalidContent { .. }));
+    }
+
+    #[test]
+    fn heap_into_string_reuses_buffer() {
+        let b = HtmlBody::new(&"A".repeat(5000)).unwrap();
+        let ptr = b.as_ptr();
+        let s = b.into_string();
+        assert_eq!(s.len(), 5000);
+        assert_eq!(s.as_ptr(), ptr);
+
+        let small = HtmlBody::new("short").unwrap();
+        assert_eq!(small.clone().into_bytes(), b"short");
+        assert_eq!(String::from(small), "short");
+    }
+
+    #[test]
+    fn heap_capacity_introspection() {
+        assert_eq!(HtmlBody::MIN_LEN, 0);
+        assert_eq!(HtmlBody::MAX_LEN, 65536);
+        assert_eq!(HtmlBody::MAX_INLINE_BYTES, 4096);
+
+        let small = HtmlBody::new("hello").unwrap();
+        assert!(!small.is_heap());
+        assert_eq!(small.capacity(), 4096);
+        assert_eq!(small.remaining_bytes(), 4091);
+        assert_eq!(small.remaining_logical(), 65531);
+
+        let big = HtmlBody::new(&"A".repeat(5000)).unwrap();
+        assert!(big.is_heap());
+        assert!(big.capacity() >= 5000);
+        assert_eq!(big.remaining_logical(), 60536);
+        assert!(!big.is_empty());
     }
 
     #[test]
@@ -288,6 +1632,238 @@ mod heap_tests {
         assert_eq!(res, 42);
         assert_eq!(b.as_str(), "Jello world");
     }
+
+    #[test]
+    fn mutate_heap_in_place() {
+        let mut b = HtmlBody::new(&"A".repeat(65536)).unwrap();
+        b.mutate(|buf, _| buf[0] = b'B').unwrap();
+        let ptr = b.as_ptr();
+
+        b.mutate(|buf, len| { buf[1] = b'C'; *len -= 1; }).unwrap();
+        assert_eq!(b.as_ptr(), ptr);
+        assert_eq!(&b[..3], "BCA");
+        assert_eq!(b.len_bytes(), 65535);
+
+        // Failed checks restore the content and length.
+        let err = b.mutate(|buf, len| { buf[2] = 0xFF; *len = 10; }).unwrap_err();
+        assert!(matches!(err, BoundedStrError::MutationFailed { .. }));
+        assert_eq!(b.len_bytes(), 65535);
+        assert_eq!(&b[..3], "BCA");
+        assert!(b.mutate(|_, len| *len = 70000).is_err());
+        assert_eq!(b.len_bytes(), 65535);
+    }
+
+    #[test]
+    fn heap_inline_and_spill() {
+        let mut b = HtmlBody::new(&"A".repeat(5000)).unwrap();
+        assert!(!b.try_inline());
+        assert!(b.is_heap());
+
+        b.truncate_logical(10).unwrap();
+        assert!(b.is_heap());
+        assert!(b.try_inline());
+        assert!(!b.is_heap());
+        assert_eq!(b.as_str(), "A".repeat(10));
+        assert_eq!(b.len_logical(), 10);
+        assert_eq!(b.capacity(), 4096);
+        assert!(b.try_inline());
+
+        b.spill_to_heap();
+        assert!(b.is_heap());
+        assert_eq!(b.as_str(), "A".repeat(10));
+        b.spill_to_heap();
+        b.try_push_str("BB").unwrap();
+        assert!(b.is_heap());
+        assert_eq!(b.len_logical(), 12);
+        assert!(b.try_inline());
+        assert_eq!(&b[8..], "AABB");
+    }
+
+    #[test]
+    fn heap_reserve_and_shrink() {
+        let mut b = HtmlBody::new("hello").unwrap();
+        b.reserve(100);
+        assert!(!b.is_heap());
+        assert_eq!(b.capacity_bytes(), 4096);
+
+        b.reserve(10_000);
+        assert!(b.is_heap());
+        assert!(b.capacity_bytes() >= 10_005);
+        let ptr = b.as_ptr();
+        for _ in 0..1000 {
+            b.try_push_str("0123456789").unwrap();
+        }
+        assert_eq!(b.as_ptr(), ptr);
+        assert_eq!(b.len_bytes(), 10_005);
+
+        b.truncate_logical(6000).unwrap();
+        b.shrink_to_fit();
+        assert_eq!(b.capacity_bytes(), 6000);
+        assert_eq!(b.capacity(), b.capacity_bytes());
+        assert_eq!(&b[..7], "hello01");
+
+        let mut secret = FlexStr::<0, 8192, 16, bounded_str::Bytes, bounded_str::AllowAll, true>::new("s3cr3t").unwrap();
+        secret.reserve(64);
+        assert!(secret.is_heap());
+        secret.shrink_to_fit();
+        assert_eq!(secret.capacity_bytes(), 6);
+        assert_eq!(secret.expose_secret(), "s3cr3t");
+    }
+
+    #[test]
+    fn heap_clone_from_reuses_buffer() {
+        let mut pooled = HtmlBody::new(&"A".repeat(8000)).unwrap();
+        let ptr = pooled.as_ptr();
+
+        pooled.clone_from(&HtmlBody::new(&"B".repeat(6000)).unwrap());
+        assert_eq!(pooled.as_ptr(), ptr);
+        assert_eq!(pooled.as_str(), "B".repeat(6000));
+
+        pooled.clone_from(&HtmlBody::new("short").unwrap());
+        assert_eq!(pooled.as_ptr(), ptr);
+        assert_eq!(pooled.as_str(), "short");
+        assert_eq!(pooled.len_logical(), 5);
+
+        let mut inline = HtmlBody::new("old value").unwrap();
+        inline.clone_from(&HtmlBody::new("new").unwrap());
+        assert!(!inline.is_heap());
+        assert_eq!(inline.as_str(), "new");
+        inline.clone_from(&HtmlBody::new(&"C".repeat(5000)).unwrap());
+        assert!(inline.is_heap());
+        assert_eq!(inline.len_bytes(), 5000);
+    }
+
+    #[test]
+    fn heap_shared_clones_and_cow() {
+        use bounded_str::SharedBoundedStr;
+        type SharedBody = SharedBoundedStr<0, 65536>;
+
+        let body = SharedBody::from(HtmlBody::new(&"A".repeat(8000)).unwrap());
+        let workers: Vec<SharedBody> = (0..8).map(|_| body.clone()).collect();
+        assert!(workers.iter().all(|w| SharedBody::ptr_eq(w, &body)));
+        assert_eq!(workers[7].len_logical(), 8000);
+
+        let mut edited = body.clone();
+        edited.mutate(|buf, len| { buf[0] = b'B'; *len = 4; }).unwrap();
+        assert_eq!(edited, "BAAA");
+        assert!(!SharedBody::ptr_eq(&edited, &body));
+        assert_eq!(&body[..4], "AAAA");
+
+        // A rejected edit keeps the shared buffer.
+        let mut rejected = body.clone();
+        assert!(rejected.mutate(|_, len| *len = 70000).is_err());
+        assert!(SharedBody::ptr_eq(&rejected, &body));
+
+        assert!(matches!(SharedBody::new(&"x".repeat(65537)), Err(BoundedStrError::TooLong { .. })));
+        let owned: HtmlBody = edited.to_bounded();
+        assert!(!owned.is_heap());
+        assert_eq!(owned.as_str(), "BAAA");
+    }
+
+    #[test]
+    fn heap_custom_allocator() {
+        use allocator_api2::alloc::{AllocError, Allocator, Global, Layout};
+        use bounded_str::BoundedStrIn;
+        use std::{cell::Cell, ptr::NonNull, rc::Rc};
+
+        #[derive(Clone, Default)]
+        struct Counting(Rc<Cell<usize>>);
+
+        unsafe impl Allocator for Counting {
+            fn allocate(&self, layout: Layout) -> Result<NonNull<[u8]>, AllocError> {
+                self.0.set(self.0.get() + 1);
+                Global.allocate(layout)
+            }
+
+            unsafe fn deallocate(&self, ptr: NonNull<u8>, layout: Layout) {
+                unsafe { Global.deallocate(ptr, layout) }
+            }
+        }
+
+        type PoolBody = BoundedStrIn<0, 65536, bounded_str::Bytes, bounded_str::AllowAll, Counting>;
+
+        let pool = Counting::default();
+        let mut body = PoolBody::new_in(&"A".repeat(5000), pool.clone()).unwrap();
+        assert_eq!(pool.0.get(), 1);
+        assert_eq!(body.len_bytes(), 5000);
+
+        body.try_push_str("BB").unwrap();
+        assert!(body.try_push_str(&"C".repeat(65536)).is_err());
+        assert_eq!(body.len_logical(), 5002);
+
+        body.mutate(|buf, len| { buf[0] = b'Z'; *len = 3; }).unwrap();
+        assert_eq!(body, "ZAA");
+        assert!(body.mutate(|_, len| *len = 70000).is_err());
+        assert_eq!(body, "ZAA");
+        assert!(pool.0.get() >= 3);
+
+        let copy: HtmlBody = body.to_bounded();
+        assert_eq!(copy.as_str(), "ZAA");
+        assert!(matches!(PoolBody::new_in(&"x".repeat(65537), pool), Err(BoundedStrError::TooLong { .. })));
+        assert_eq!(BoundedStrIn::<1, 8>::new("global").unwrap().len_logical(), 6);
+    }
+
+    #[test]
+    fn heap_bump_arena() {
+        use bounded_str::BumpStr;
+        type Body<'b> = BumpStr<'b, 0, 65536>;
+
+        let mut arena = bumpalo::Bump::new();
+        for _ in 0..3 {
+            let bodies: Vec<Body<'_>> = (0..1000).map(|i| Body::new_in(&format!("body-{i}-{}", "x".repeat(100)), &arena).unwrap()).collect();
+            assert_eq!(bodies[999].as_str().len(), 109);
+            assert!(std::ptr::eq(*bodies[0].allocator(), &arena));
+            assert!(arena.allocated_bytes() >= 1000 * 100);
+            drop(bodies);
+            arena.reset();
+        }
+        assert!(Body::new_in(&"x".repeat(65537), &arena).is_err());
+    }
+
+    #[test]
+    fn heap_only_alias() {
+        use bounded_str::HeapStr;
+        type Body = HeapStr<0, 65536>;
+
+        assert_eq!(std::mem::size_of::<Body>(), 2 * std::mem::size_of::<usize>());
+        assert!(std::mem::size_of::<HtmlBody>() > 4096);
+
+        let mut body = Body::default();
+        assert!(!body.is_heap());
+        body.try_push_str("a").unwrap();
+        assert!(body.is_heap());
+        let big = Body::new(&"x".repeat(65536)).unwrap();
+        assert!(big.is_heap());
+        assert!(matches!(Body::new(&"x".repeat(65537)), Err(BoundedStrError::TooLong { .. })));
+
+        // Closure edits grow an empty value on the heap too.
+        let mut empty = Body::default();
+        empty.mutate(|buf, len| { buf[..3].copy_from_slice(b"abc"); *len = 3; }).unwrap();
+        assert_eq!(empty, "abc");
+    }
+
+    #[test]
+    fn heap_zero_copy_bytes() {
+        let payload = bytes::Bytes::from("<p>".repeat(3000));
+        let body = HtmlBody::try_from_bytes(payload.clone()).unwrap();
+        assert_eq!(body.as_bytes().as_ptr(), payload.as_ptr());
+        assert_eq!(body.len_logical(), 9000);
+        assert!(body.is_heap());
+
+        // Clones share the payload; a write copies it out first.
+        let mut edited = body.clone();
+        assert_eq!(edited.as_bytes().as_ptr(), payload.as_ptr());
+        edited.try_push_str("</p>").unwrap();
+        assert_ne!(edited.as_bytes().as_ptr(), payload.as_ptr());
+        assert_eq!(&body[..3], "<p>");
+        assert!(edited.ends_with("<p></p>"));
+
+        let back: bytes::Bytes = body.into();
+        assert_eq!(back.as_ptr(), payload.as_ptr());
+
+        assert!(matches!(HtmlBody::try_from_bytes(bytes::Bytes::from_static(b"\xff")), Err(BoundedStrError::InvalidUtf8)));
+        assert!(matches!(HtmlBody::try_from_bytes(bytes::Bytes::from(vec![b'x'; 65537])), Err(BoundedStrError::TooLong { .. })));
+    }
 }
 
 #[cfg(test)]
@@ -298,6 +1874,16 @@ mod stress_tests {
     // Heap-heavy FlexStr: small stack buffer, всё >8KiB идёт на heap
     type BigChunk = FlexStr<1, {1024*1024}, {8*1024}, bounded_str::Bytes>;
 
+    #[test]
+    fn stress_chunks_of() {
+        let input = "Ā".repeat(16 * 1024 * 1024);
+        let start = Instant::now();
+        let chunks: Vec<BigChunk> = BigChunk::chunks_of(&input).collect::<Result<_, _>>().expect("All chunks fit");
+        println!("Split 32 MiB into {} chunks in {:.6} sec", chunks.len(), start.elapsed().as_secs_f64());
+        assert_eq!(chunks.len(), 32);
+        assert!(chunks.iter().all(|c| c.len_bytes() == 1024 * 1024));
+    }
+
     #[test]
     fn stress_1gb() {
         let chunk_size = 1024 * 1024; // 1 MiB per chunk
@@ -372,7 +1958,7 @@ mod security_tests {
     #[test]
     #[cfg(feature = "constant-time")]
     fn test_constant_time_equality() {
-        type Secret = BoundedStr<1, 32, 32, Bytes, AllowAll, true>;
+        type Secret = BoundedStr<1, 32, 32, Bytes, AllowAll, true, ConstantTime>;
         
         let s1 = Secret::new("password123").unwrap();
         let s2 = Secret::new("password123").unwrap();
@@ -387,6 +1973,131 @@ mod security_tests {
         assert!(s1 != s4);
     }
 
+    #[test]
+    fn test_secrets_are_redacted() {
+        type Password = StackStr<1, 32, 32, Bytes, AllowAll, true>;
+        let pw = Password::new("hunter2").unwrap();
+
+        assert_eq!(format!("{pw}"), "***REDACTED***");
+        assert_eq!(format!("{pw:?}"), "BoundedStr(***REDACTED***)");
+        assert_eq!(format!("{pw:>16}"), "  ***REDACTED***");
+        assert_eq!(pw.expose_secret(), "hunter2");
+
+        let name = StackStr::<1, 32>::new("alice").unwrap();
+        assert_eq!(format!("{name}"), "alice");
+        assert!(format!("{name:?}").contains("\"alice\""));
+    }
+
+    #[test]
+    fn test_secret_bounded_str() {
+        use secrecy::ExposeSecret;
+        type ApiKey = SecretBoundedStr<8, 64>;
+
+        let key = ApiKey::new("sk-live-1234").unwrap();
+        assert_eq!(format!("{key:?}"), "BoundedStr(***REDACTED***)");
+        assert_eq!(<ApiKey as ExposeSecret<str>>::expose_secret(&key), "sk-live-1234");
+        assert!(key == ApiKey::new("sk-live-1234").unwrap());
+        let _: BoundedStr<8, 64, 64, Bytes, AllowAll, true, ConstantTime> = key;
+    }
+
+    #[test]
+    fn test_masked() {
+        type Card = SecretBoundedStr<12, 19>;
+        let card = Card::new("4111111111111234").unwrap();
+        assert_eq!(format!("{}", card.masked(4)), "****1234");
+        assert_eq!(format!("{}", card.masked(0)), "****");
+        assert_eq!(format!("{}", card.masked(16)), "****");
+
+        let name = StackStr::<1, 32, 32, Chars>::new("Алёна").unwrap();
+        assert_eq!(format!("{}", name.masked(2)), "****на");
+    }
+
+    #[test]
+    fn test_keyed_hash() {
+        use std::hash::{BuildHasher, RandomState};
+
+        struct KeyA;
+        impl HashKey for KeyA { fn key() -> (u64, u64) { (1, 2) } }
+        struct KeyB;
+        impl HashKey for KeyB { fn key() -> (u64, u64) { (3, 4) } }
+
+        type SecretA = StackStr<1, 32, 32, Bytes, AllowAll, true, KeyedHash<KeyA>>;
+        type SecretB = StackStr<1, 32, 32, Bytes, AllowAll, true, KeyedHash<KeyB>>;
+
+        let state = RandomState::new();
+        let a = SecretA::new("hunter2").unwrap();
+        assert_eq!(state.hash_one(&a), state.hash_one(SecretA::new("hunter2").unwrap()));
+        assert_ne!(state.hash_one(&a), state.hash_one("hunter2"));
+        assert_ne!(state.hash_one(&a), state.hash_one(SecretB::new("hunter2").unwrap()));
+        assert!(a == SecretB::new("hunter2").unwrap());
+
+        let mut map = std::collections::HashMap::new();
+        map.insert(a.clone(), "alice");
+        assert_eq!(map.get(&a), Some(&"alice"));
+    }
+
+    #[test]
+    fn test_mlock_heap_secret() {
+        type Blob = FlexStr<1, 1_048_576, 16, Bytes, AllowAll, true>;
+
+        let mut secret = Blob::new(&"k".repeat(256 * 1024)).unwrap();
+        #[cfg(target_os = "linux")]
+        {
+            let status = std::fs::read_to_string("/proc/self/status").unwrap();
+            let locked_kb: usize = status.lines()
+                .find_map(|l| l.strip_prefix("VmLck:"))
+                .and_then(|v| v.trim().trim_end_matches("kB").trim().parse().ok())
+                .unwrap();
+            assert!(locked_kb >= 256, "only {locked_kb} kB locked");
+        }
+
+        // Growing, editing and cloning keep working on the locked buffer.
+        secret.try_push_str(&"x".repeat(300 * 1024)).unwrap();
+        secret.mutate(|buf, len| buf[*len - 1] = b'y').unwrap();
+        let copy = secret.clone();
+        assert!(copy.ends_with("xy"));
+        assert_eq!(secret.into_string().len(), 556 * 1024);
+    }
+
+    #[test]
+    fn test_eq_policy_per_type() {
+        type Key = StackStr<1, 32>;
+        type Secret = StackStr<1, 32, 32, Bytes, AllowAll, true, ConstantTime>;
+
+        assert!(!<Key as PartialEq>::eq(&Key::new("a").unwrap(), &Key::new("b").unwrap()));
+        const { assert!(!FastEq::CONSTANT_TIME && ConstantTime::CONSTANT_TIME) };
+
+        // Mixed comparisons work both ways and agree with plain `str` equality.
+        let key = Key::new("token").unwrap();
+        let secret = Secret::new("token").unwrap();
+        assert!(key == secret);
+        assert!(secret == key);
+        assert_eq!(secret, "token");
+        assert_eq!("token", secret);
+        assert!(secret != Secret::new("tokem").unwrap());
+
+        let mut map = std::collections::HashMap::new();
+        map.insert(key.clone(), 1);
+        assert_eq!(map.get(&key), Some(&1));
+    }
+
+    #[test]
+    #[cfg(feature = "constant-time")]
+    fn test_ct_eq_choice() {
+        use subtle::ConstantTimeEq;
+        type Secret = BoundedStr<1, 32, 32, Bytes, AllowAll, true>;
+
+        let key = Secret::new("password123").unwrap();
+        assert!(bool::from(key.ct_eq(b"password123")));
+        assert!(!bool::from(key.ct_eq("password124")));
+        assert!(!bool::from(key.ct_eq(b"pass")));
+
+        // Composes with other constant-time checks.
+        let other = Secret::new("password123").unwrap();
+        let both = ConstantTimeEq::ct_eq(&key, &other) & key.ct_eq(other.as_bytes());
+        assert!(bool::from(both));
+    }
+
     // 2. Тест на Zeroize (Логический)
     // Напрямую проверить RAM после drop сложно без внешних инструментов, 
     // но мы можем проверить, что мутатор корректно затирает временную копию.
@@ -403,7 +2114,7 @@ mod security_tests {
             *len = 1; // Ошибка: меньше MIN (5)
         });
 
-        assert!(res.is_err());
+        assert!(matches!(res, Err(BoundedStrError::MutationFailed { kind: MutationErrorKind::LengthViolation })));
         // Оригинал не должен измениться
         assert_eq!(s.as_str(), "valid");
     }
@@ -419,6 +2130,60 @@ mod security_tests {
         let _sec = MySecret::new("secret").unwrap();
     }
 
+    #[test]
+    fn test_secret_mutate_panic_keeps_original() {
+        type Secret = StackStr<1, 16, 16, Bytes, AllowAll, true>;
+        type SecretHeap = FlexStr<1, 100, 4, Bytes, AllowAll, true>;
+
+        let mut s = Secret::new("secret").unwrap();
+        let res = std::panic::catch_unwind(std::panic::AssertUnwindSafe(|| {
+            s.mutate(|buf, _| { buf[0] = b'X'; panic!("mutator failed"); })
+        }));
+        assert!(res.is_err());
+        assert_eq!(s.as_str(), "secret");
+
+        let mut h = SecretHeap::new("heap secret").unwrap();
+        assert!(h.mutate(|buf, len| { buf[..4].copy_from_slice(b"\xFF\xFF\xFF\xFF"); *len = 4; }).is_err());
+        assert_eq!(h.as_str(), "heap secret");
+        h.mutate(|buf, len| { buf[..4].copy_from_slice(b"heap"); *len = 4; }).unwrap();
+        assert_eq!(h.as_str(), "heap");
+    }
+
+    #[test]
+    fn test_secret_heap_growth() {
+        type SecretHeap = FlexStr<1, 4096, 8, Bytes, AllowAll, true>;
+        let mut s = SecretHeap::new("heap secret").unwrap();
+        let mut expected = String::from("heap secret");
+        for i in 0..200 {
+            let chunk = if i % 2 == 0 { "ab" } else { "cde" };
+            s.try_push_str(chunk).unwrap();
+            s.try_insert_str(0, "x").unwrap();
+            expected.push_str(chunk);
+            expected.insert(0, 'x');
+        }
+        assert_eq!(s.as_str(), expected);
+        assert!(s.capacity() >= s.len_bytes());
+    }
+
+    #[test]
+    #[cfg(feature = "zeroize")]
+    fn test_zeroize_trait() {
+        use zeroize::{Zeroize, ZeroizeOnDrop};
+
+        fn assert_zeroize_on_drop<T: ZeroizeOnDrop>() {}
+        type Secret = FlexStr<1, 100, 10, Bytes, AllowAll, true>;
+        assert_zeroize_on_drop::<Secret>();
+
+        let mut s = Secret::new("long_secret_string").unwrap();
+        s.zeroize();
+        assert!(s.is_empty());
+        assert!(!s.is_heap());
+
+        let mut s = StackStr::<1, 10>::new("secret").unwrap();
+        s.zeroize();
+        assert_eq!(s.as_str(), "");
+    }
+
     #[test]
     #[cfg(feature = "alloc")]
     fn test_flex_str_zeroize() {
@@ -449,10 +2214,24 @@ mod security_tests {
 		});
 
 		// Должно вернуть MutationFailed, а старая "огонь" должна выжить
-		assert!(res.is_err());
+		assert!(matches!(res, Err(BoundedStrError::MutationFailed { kind: MutationErrorKind::InvalidUtf8 })));
 		assert_eq!(s.as_str(), "🔥");
 	}
+
 	#[test]
+	fn mutate_reports_policy_violation() {
+		type Ascii = StackStr<1, 16, 16, Bytes, AsciiOnly>;
+		let mut s = Ascii::new("abc").unwrap();
+
+		let res = s.mutate(|buf, len| {
+			buf[..2].copy_from_slice("é".as_bytes());
+			*len = 3;
+		});
+		assert!(matches!(res, Err(BoundedStrError::MutationFailed { kind: MutationErrorKind::PolicyViolation })));
+		assert_eq!(s.as_str(), "abc");
+	}
+	#[test]
+	#[allow(clippy::needless_range_loop)]
 	fn crash_test_stack_boundary() {
 		// Буфер ровно 5 байт
 		type Fixed = StackStr<1, 5, 5, Bytes>;
@@ -501,7 +2280,7 @@ mod security_tests {
 			*len = 200;
 		});
 
-		assert!(matches!(res, Err(BoundedStrError::TooManyBytes)));
+		assert!(matches!(res, Err(BoundedStrError::TooManyBytes { .. })));
 	}
 	
 	#[test]
@@ -526,3 +2305,379 @@ mod security_tests {
 }
 
 
+
+#[cfg(test)]
+mod edit_tests {
+    use bounded_str::*;
+
+    #[test]
+    fn push_str_respects_bounds() {
+        type Name = StackStr<1, 8, 8, Chars, AsciiOnly>;
+        let mut s = Name::new("abc").unwrap();
+
+        s.try_push_str("def").unwrap();
+        s.try_push('g').unwrap();
+        assert_eq!(s.as_str(), "abcdefg");
+
+        assert!(matches!(s.try_push('🔥'), Err(BoundedStrError::InvalidContent { .. })));
+        assert!(matches!(s.try_push_str("xyz"), Err(BoundedStrError::TooLong { .. })));
+        assert_eq!(s.as_str(), "abcdefg");
+    }
+
+    #[test]
+    fn push_str_spills_to_heap() {
+        type Body = FlexStr<0, 64, 4>;
+        let mut s = Body::new("abc").unwrap();
+
+        s.try_push_str("defgh").unwrap();
+        assert_eq!(s.as_str(), "abcdefgh");
+
+        s.try_push('!').unwrap();
+        assert_eq!(s.as_str(), "abcdefgh!");
+        assert_eq!(s.len_bytes(), 9);
+    }
+
+    #[test]
+    fn pop_and_truncate_are_char_safe() {
+        type Name = StackStr<2, 8, 32, Chars>;
+        let mut s = Name::new("aб🔥в").unwrap();
+
+        assert_eq!(s.pop(), Some('в'));
+        assert_eq!(s.as_str(), "aб🔥");
+
+        s.truncate_logical(2).unwrap();
+        assert_eq!(s.as_str(), "aб");
+
+        assert!(matches!(s.truncate_logical(1), Err(BoundedStrError::TooShort { .. })));
+        assert_eq!(s.pop(), None);
+        assert_eq!(s.as_str(), "aб");
+    }
+
+    #[test]
+    fn try_pop_reports_rejection() {
+        type Name = StackStr<2, 8, 32, Chars>;
+        let mut s = Name::new("abc").unwrap();
+
+        assert_eq!(s.try_pop(), Ok(Some('c')));
+        assert!(matches!(s.try_pop(), Err(BoundedStrError::TooShort { actual: 1, min: 2 })));
+        assert_eq!(s.as_str(), "ab");
+
+        let mut empty = StackStr::<0, 8>::new("").unwrap();
+        assert_eq!(empty.try_pop(), Ok(None));
+    }
+
+    #[test]
+    fn truncate_bytes_rounds_down_to_boundary() {
+        type Raw = StackStr<0, 16, 16, Bytes>;
+        let mut s = Raw::new("ab🔥").unwrap();
+        s.truncate_logical(4).unwrap();
+        assert_eq!(s.as_str(), "ab");
+    }
+
+    #[test]
+    fn clear_requires_zero_min() {
+        let mut empty_ok = StackStr::<0, 8>::new("abc").unwrap();
+        empty_ok.clear().unwrap();
+        assert!(empty_ok.is_empty());
+
+        let mut non_empty = StackStr::<1, 8>::new("abc").unwrap();
+        assert!(matches!(non_empty.clear(), Err(BoundedStrError::TooShort { .. })));
+        assert_eq!(non_empty.as_str(), "abc");
+    }
+
+    #[test]
+    fn editor_commits_once() {
+        type Name = StackStr<3, 8, 16, Chars, AsciiOnly>;
+        let mut s = Name::new("hello").unwrap();
+
+        let mut editor = s.edit();
+        editor.truncate(0).unwrap();
+        assert_eq!(editor.as_str(), "");
+        editor.push_str("world").unwrap()
+            .insert(0, "[").unwrap()
+            .replace_range(1..3, "W").unwrap()
+            .remove_range(4..5).unwrap()
+            .push_str("]").unwrap();
+        assert_eq!(editor.as_str(), "[Wrl]");
+        editor.commit().unwrap();
+
+        assert_eq!(s.as_str(), "[Wrl]");
+    }
+
+    #[test]
+    fn editor_rolls_back_on_drop_and_failed_commit() {
+        type Name = StackStr<3, 8, 32, Chars>;
+        let mut s = Name::new("тест").unwrap();
+
+        {
+            let mut editor = s.edit();
+            editor.push_str("!!!").unwrap();
+        }
+        assert_eq!(s.as_str(), "тест");
+
+        let mut editor = s.edit();
+        assert_eq!(editor.insert(1, "x").err(), Some(BoundedStrError::NotCharBoundary));
+        editor.truncate(2).unwrap();
+        assert!(matches!(editor.commit(), Err(BoundedStrError::TooShort { .. })));
+        assert_eq!(s.as_str(), "тест");
+    }
+
+    #[test]
+    fn insert_and_remove_check_boundaries() {
+        type Name = StackStr<2, 10, 32, Chars>;
+        let mut s = Name::new("ая").unwrap();
+
+        s.try_insert_str(2, "бв").unwrap();
+        assert_eq!(s.as_str(), "абвя");
+        assert_eq!(s.try_insert_str(1, "x"), Err(BoundedStrError::NotCharBoundary));
+        assert_eq!(s.try_insert_str(100, "x"), Err(BoundedStrError::NotCharBoundary));
+
+        s.try_remove_range(2..6).unwrap();
+        assert_eq!(s.as_str(), "ая");
+        assert_eq!(s.try_remove_range(0..1), Err(BoundedStrError::NotCharBoundary));
+        assert!(matches!(s.try_remove_range(0..2), Err(BoundedStrError::TooShort { .. })));
+        assert_eq!(s.as_str(), "ая");
+    }
+
+    #[test]
+    fn retain_filters_in_place() {
+        type Code = StackStr<3, 8, 32, Chars>;
+        let mut s = Code::new("a-b-c-🔥").unwrap();
+
+        s.retain(|c| c != '-').unwrap();
+        assert_eq!(s.as_str(), "abc🔥");
+
+        assert!(matches!(s.retain(|c| c == 'a'), Err(BoundedStrError::TooShort { .. })));
+        assert_eq!(s.as_str(), "abc🔥");
+    }
+
+    #[test]
+    fn replace_returns_new_bounded_value() {
+        type Path = FlexStr<1, 32, 8, Bytes, AsciiOnly>;
+        let p = Path::new("a/b/c").unwrap();
+
+        let q = p.try_replace("/", "::").unwrap();
+        assert_eq!(q.as_str(), "a::b::c");
+        assert_eq!(p.as_str(), "a/b/c");
+
+        assert!(matches!(p.try_replace("/", "ё").unwrap_err(), BoundedStrError::InvalidContent { .. }));
+        assert!(matches!(p.try_replace("/", &"x".repeat(20)).unwrap_err(), BoundedStrError::TooLong { .. }));
+    }
+
+    #[test]
+    fn case_conversion_rechecks_bounds() {
+        type Tag = StackStr<1, 4, 8, Chars>;
+        let s = Tag::new("Aßb").unwrap();
+        assert_eq!(s.try_to_lowercase().unwrap().as_str(), "aßb");
+        assert_eq!(s.try_to_uppercase().unwrap().as_str(), "ASSB");
+
+        // 'ß' upper-cases to "SS", pushing the value past MAX chars.
+        let s = Tag::new("ßßß").unwrap();
+        assert!(matches!(s.try_to_uppercase().unwrap_err(), BoundedStrError::TooLong { .. }));
+
+        // Word-final sigma follows `str::to_lowercase`.
+        type Greek = StackStr<1, 32, 64, Chars>;
+        for word in ["ΟΔΟΣ", "ΟΔΟΣ ΣΟΦΙΑΣ", "Σ", "ΣΑ", "ΑΣ.", "ΑΣ'Α"] {
+            assert_eq!(Greek::new(word).unwrap().try_to_lowercase().unwrap(), word.to_lowercase().as_str(), "{word}");
+        }
+        assert_eq!(Greek::new("ΟΔΟΣ").unwrap().try_to_lowercase().unwrap(), "οδος");
+    }
+
+    #[test]
+    fn chars_collect_and_extend() {
+        type Word = FlexStr<1, 8, 4, Chars>;
+        let w = Word::try_from_chars("hello".chars().rev()).unwrap();
+        assert_eq!(w.as_str(), "olleh");
+
+        // An endless iterator is cut off as soon as the bound is exceeded.
+        assert!(matches!(Word::try_from_chars(core::iter::repeat('ы')).unwrap_err(), BoundedStrError::TooLong { .. }));
+
+        let mut w = w;
+        w.try_extend("!?".chars()).unwrap();
+        assert_eq!(w.as_str(), "olleh!?");
+        assert!(matches!(w.try_extend(core::iter::repeat('x')), Err(BoundedStrError::TooLong { .. })));
+        assert_eq!(w.as_str(), "olleh!?");
+    }
+
+    #[test]
+    fn concat_and_add() {
+        type Id = StackStr<1, 12, 12, Bytes, AsciiOnly>;
+        let base = Id::new("user").unwrap();
+
+        let full = base.try_concat("-42").unwrap();
+        assert_eq!(full.as_str(), "user-42");
+        assert_eq!(base.as_str(), "user");
+
+        let full = ((&base + ":").unwrap() + "admin").unwrap();
+        assert_eq!(full.as_str(), "user:admin");
+
+        assert!(matches!((full + "-too-long").unwrap_err(), BoundedStrError::TooLong { .. }));
+        assert!(matches!(base.try_concat("ß").unwrap_err(), BoundedStrError::InvalidContent { .. }));
+    }
+
+    #[test]
+    fn repeat_is_bounded() {
+        type Line = FlexStr<0, 10, 4, Chars>;
+        let dash = Line::new("-=").unwrap();
+
+        assert_eq!(dash.try_repeat(3).unwrap().as_str(), "-=-=-=");
+        assert_eq!(dash.try_repeat(0).unwrap().as_str(), "");
+        assert!(matches!(dash.try_repeat(usize::MAX).unwrap_err(), BoundedStrError::TooLong { .. }));
+        assert_eq!(Line::new("").unwrap().try_repeat(usize::MAX).unwrap().as_str(), "");
+    }
+
+    #[test]
+    fn fmt_write_is_atomic() {
+        use std::fmt::Write as _;
+        type Key = StackStr<1, 12, 12, Bytes, AsciiOnly>;
+        let mut s = Key::new("k").unwrap();
+
+        write!(s, ":user-{}", 42).unwrap();
+        assert_eq!(s.as_str(), "k:user-42");
+
+        // The first fragment would fit, but the whole write does not.
+        assert!(write!(s, "-{}", 12345).is_err());
+        assert_eq!(s.as_str(), "k:user-42");
+
+        let mut editor = s.edit();
+        write!(editor, "#{}", 7).unwrap();
+        editor.commit().unwrap();
+        assert_eq!(s.as_str(), "k:user-42#7");
+    }
+
+    #[test]
+    fn bounded_format_macro() {
+        type Key = StackStr<1, 12, 12, Bytes, AsciiOnly>;
+        type Body = FlexStr<1, 64, 8>;
+        let id = 42;
+
+        let k = bounded_format!(Key, "user-{id}").unwrap();
+        assert_eq!(k.as_str(), "user-42");
+        assert_eq!(bounded_format!(Key, "static").unwrap().as_str(), "static");
+
+        let b = bounded_format!(Body, "{}:{:>10}", k, id).unwrap();
+        assert_eq!(b.as_str(), "user-42:        42");
+
+        assert!(matches!(bounded_format!(Key, "{:>100}", id).unwrap_err(), BoundedStrError::TooLong { .. }));
+        assert!(matches!(bounded_format!(Body, "{:>65535}", id).unwrap_err(), BoundedStrError::TooLong { .. }));
+        assert!(matches!(bounded_format!(Key, "{}", "ключ").unwrap_err(), BoundedStrError::InvalidContent { .. }));
+    }
+
+    #[test]
+    fn literal_macro_is_checked_at_compile_time() {
+        type Name = StackStr<3, 16, 64, Chars, AsciiOnly>;
+        type Label = StackStr<1, 4, 16, Chars>;
+
+        let n = bounded_str!(Name, "Alice");
+        assert_eq!(n.as_str(), "Alice");
+        let l = bounded_str!(Label, "ёжик");
+        assert_eq!(l.len_logical(), 4);
+
+        const CHECK: Result<(), BoundedStrError> = Name::check_const("Al");
+        assert_eq!(CHECK, Err(BoundedStrError::TooShort { actual: 2, min: 3 }));
+        assert_eq!(
+            Name::check_const("AЛиса"),
+            Err(BoundedStrError::InvalidContent { byte_offset: 1, char: Some('Л') })
+        );
+        assert!(matches!(Label::check_const("ёжики"), Err(BoundedStrError::TooLong { .. })));
+    }
+
+    static DEFAULT_NAME: StackStr<3, 16> = StackStr::new_const("guest");
+    static DEFAULT_LABEL: StackStr<1, 4, 16, Chars> = bounded_str!(StackStr<1, 4, 16, Chars>, "ёжик");
+
+    #[test]
+    fn const_construction_for_statics() {
+        assert_eq!(DEFAULT_NAME.as_str(), "guest");
+        assert_eq!(DEFAULT_LABEL.len_bytes(), 8);
+
+        let mut name = DEFAULT_NAME.clone();
+        name.try_push_str("_1").unwrap();
+        assert_eq!(name, StackStr::<3, 16>::new("guest_1").unwrap());
+    }
+
+    #[test]
+    fn default_is_empty_when_min_is_zero() {
+        #[derive(Default)]
+        struct Profile {
+            bio: FlexStr<0, 256, 16, Chars>,
+            nick: StackStr<0, 16, 16, Bytes, AsciiOnly>,
+        }
+
+        let p = Profile::default();
+        assert!(p.bio.is_empty());
+        assert_eq!(p.nick.as_str(), "");
+    }
+
+    #[test]
+    fn default_requires_policy_accepting_empty() {
+        // Only policies that guarantee `check("")` get `Default`; `Not<_>` and
+        // regex policies don't, so `Default::default()` for them fails to compile.
+        struct Opted;
+        impl FormatPolicy for Opted {
+            const ACCEPTS_EMPTY: bool = true;
+            fn check(s: &str) -> bool { !s.contains('!') }
+        }
+
+        const {
+            assert!(!<Not<AsciiOnly> as FormatPolicy>::ACCEPTS_EMPTY);
+            assert!(<And<AsciiOnly, NoControlChars> as FormatPolicy>::ACCEPTS_EMPTY);
+            assert!(<Or<Not<AsciiOnly>, Identifier> as FormatPolicy>::ACCEPTS_EMPTY);
+        };
+        assert_eq!(StackStr::<0, 8, 8, Bytes, And<PrintableAscii, Opted>>::default().as_str(), "");
+    }
+
+    #[test]
+    fn widen_and_narrow() {
+        type Strict = StackStr<3, 16, 16, Chars>;
+        type Loose = StackStr<0, 64, 64, Chars>;
+        type Tiny = StackStr<1, 4, 16, Chars>;
+
+        let s = Strict::new("alice").unwrap();
+        let wide: Loose = s.clone().widen();
+        assert_eq!(wide, s);
+
+        let back: Strict = wide.narrow().unwrap();
+        assert_eq!(back.as_str(), "alice");
+        assert!(matches!(back.narrow::<1, 4, 16>().unwrap_err(), BoundedStrError::TooLong { .. }));
+        assert!(matches!(Loose::new("").unwrap().narrow::<3, 16, 16>().unwrap_err(), BoundedStrError::TooShort { .. }));
+
+        let t: Tiny = Strict::new("ёжик").unwrap().narrow().unwrap();
+        assert_eq!(t.as_str(), "ёжик");
+
+        let heap = FlexStr::<1, 64, 4>::new("ёжики").unwrap();
+        let wide: FlexStr<0, 128, 4> = heap.widen();
+        assert_eq!(wide.as_str(), "ёжики");
+    }
+
+    #[test]
+    fn refine_and_relax_policies() {
+        type Raw = FlexStr<1, 64, 4>;
+        type Ascii = FlexStr<1, 64, 4, Bytes, AsciiOnly>;
+
+        let raw = Raw::new("plain-ascii").unwrap();
+        let ptr = raw.as_ptr();
+        let ascii: Ascii = raw.refine().unwrap();
+        assert_eq!(ascii.as_ptr(), ptr);
+
+        let back: Raw = ascii.relax();
+        assert_eq!(back.as_str(), "plain-ascii");
+
+        assert!(matches!(Raw::new("ёж").unwrap().refine::<AsciiOnly>().unwrap_err(), BoundedStrError::InvalidContent { .. }));
+    }
+
+    #[test]
+    fn refine_and_relax_keep_eq_policy() {
+        fn constant_time<const MIN: usize, const MAX: usize, const MB: usize, L: LengthPolicy, F: FormatPolicy, const Z: bool, C: EqPolicy>(
+            _: &BoundedStr<MIN, MAX, MB, L, F, Z, C>,
+        ) -> bool {
+            C::CONSTANT_TIME
+        }
+
+        let secret = StackStr::<1, 32, 32, Bytes, AsciiOnly, true, ConstantTime>::new("hunter2").unwrap();
+        let relaxed = secret.relax::<AllowAll>();
+        assert!(constant_time(&relaxed));
+        let refined = relaxed.refine::<AsciiOnly>().unwrap();
+        assert!(constant_time(&refined));
+        let _: StackStr<1, 32, 32, Bytes, AsciiOnly, true, ConstantTime> = refined;
+    }
+}
@@ -1,378 +1,4724 @@
 #![no_std]
 #[cfg(feature = "alloc")]
 extern crate alloc;
+#[cfg(feature = "std")]
+extern crate std;
 #[cfg(feature = "alloc")]
-use alloc::{vec::Vec};
+use alloc::{string::String, vec::Vec};
 
 use core::{
+    borrow::Borrow,
+    ffi::CStr,
+    cmp::Ordering,
     fmt::{self, Display, Formatter},
     hash::{Hash, Hasher},
     marker::PhantomData,
-    ops::Deref,
+    mem::{ManuallyDrop, MaybeUninit},
+    num::NonZero,
+    ops::{Add, Deref, Index, Range},
+    panic::{RefUnwindSafe, UnwindSafe},
+    slice::SliceIndex,
     str::{self, FromStr},
 };
 
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum ConstLength {
+    Unsupported,
+    Bytes,
+    Chars,
+    Utf16,
+}
+
 pub trait LengthPolicy {
+    const CONST_LENGTH: ConstLength = ConstLength::Unsupported;
+    // Unit name used in error messages, e.g. "24 chars, max 16".
+    const UNIT: &'static str = "units";
+
     fn logical_len(s: &str) -> usize;
+
+    // Largest char boundary whose prefix is at most `n` logical units long.
+    fn byte_offset(s: &str, n: usize) -> usize {
+        let mut end = 0;
+        for (i, c) in s.char_indices() {
+            let next = i + c.len_utf8();
+            if Self::logical_len(&s[..next]) > n { break; }
+            end = next;
+        }
+        end
+    }
 }
 
 #[derive(Clone, Copy, Debug, PartialEq, Eq, Default)]
 pub struct Bytes;
 impl LengthPolicy for Bytes {
+    const CONST_LENGTH: ConstLength = ConstLength::Bytes;
+    const UNIT: &'static str = "bytes";
+
     #[inline(always)] fn logical_len(s: &str) -> usize { s.len() }
+
+    fn byte_offset(s: &str, n: usize) -> usize {
+        let mut end = n.min(s.len());
+        while !s.is_char_boundary(end) { end -= 1; }
+        end
+    }
 }
 
 #[derive(Clone, Copy, Debug, PartialEq, Eq, Default)]
 pub struct Chars;
 impl LengthPolicy for Chars {
-    #[inline(always)] fn logical_len(s: &str) -> usize { s.chars().count() }
+    const CONST_LENGTH: ConstLength = ConstLength::Chars;
+    const UNIT: &'static str = "chars";
+
+    #[inline(always)] fn logical_len(s: &str) -> usize { count_chars(s) }
+
+    fn byte_offset(s: &str, n: usize) -> usize {
+        s.char_indices().nth(n).map_or(s.len(), |(i, _)| i)
+    }
+}
+
+// User-perceived characters: extended grapheme clusters, so "e\u{301}" and
+// a ZWJ emoji sequence count as one each.
+#[cfg(feature = "unicode-segmentation")]
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Default)]
+pub struct Graphemes;
+#[cfg(feature = "unicode-segmentation")]
+impl LengthPolicy for Graphemes {
+    const UNIT: &'static str = "graphemes";
+
+    fn logical_len(s: &str) -> usize {
+        unicode_segmentation::UnicodeSegmentation::graphemes(s, true).count()
+    }
+
+    fn byte_offset(s: &str, n: usize) -> usize {
+        unicode_segmentation::UnicodeSegmentation::grapheme_indices(s, true).nth(n).map_or(s.len(), |(i, _)| i)
+    }
+}
+
+// Rendered terminal columns: East Asian wide chars count 2, combining marks 0.
+#[cfg(feature = "unicode-width")]
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Default)]
+pub struct DisplayWidth;
+#[cfg(feature = "unicode-width")]
+impl LengthPolicy for DisplayWidth {
+    const UNIT: &'static str = "columns";
+
+    fn logical_len(s: &str) -> usize { unicode_width::UnicodeWidthStr::width(s) }
+}
+
+// UTF-16 code units, as JavaScript, SQL Server `NVARCHAR` and Windows APIs count
+// them: chars outside the BMP take 2.
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Default)]
+pub struct Utf16Units;
+impl LengthPolicy for Utf16Units {
+    const CONST_LENGTH: ConstLength = ConstLength::Utf16;
+    const UNIT: &'static str = "UTF-16 units";
+
+    #[inline(always)] fn logical_len(s: &str) -> usize { s.chars().map(char::len_utf16).sum() }
+
+    fn byte_offset(s: &str, n: usize) -> usize {
+        let mut units = 0;
+        for (i, c) in s.char_indices() {
+            units += c.len_utf16();
+            if units > n { return i; }
+        }
+        s.len()
+    }
+}
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum ConstFormat {
+    Unsupported,
+    AllowAll,
+    AsciiOnly,
+    // Bit `b` set: ASCII byte `b` is allowed. See `Charset`.
+    Charset(u128),
 }
 
 pub trait FormatPolicy {
+    const CONST_FORMAT: ConstFormat = ConstFormat::Unsupported;
+
+    // Whether `check("")` is guaranteed to pass; `Default` requires it at compile
+    // time. Policies with a `CONST_FORMAT` always accept it, others must opt in.
+    const ACCEPTS_EMPTY: bool = !matches!(Self::CONST_FORMAT, ConstFormat::Unsupported);
+
     fn check(s: &str) -> bool;
+
+    // Byte offset of the first offending char, for policies that can point at one.
+    fn invalid_at(_s: &str) -> Option<usize> { None }
+
+    fn name() -> &'static str { core::any::type_name::<Self>() }
+
+    // ECMA-262 regex matching exactly the accepted strings, for JSON Schema output.
+    fn json_pattern() -> Option<&'static str> { None }
+
+    // Measures `s` with `L` and checks it in one call. Override to fuse both
+    // into a single pass; the default walks the string once for each.
+    fn validate<L: LengthPolicy>(s: &str) -> Result<usize, Violation> {
+        let logical_len = L::logical_len(s);
+        if Self::check(s) { return Ok(logical_len); }
+        Err(Violation { logical_len, byte_offset: Self::invalid_at(s) })
+    }
+}
+
+// A failed `FormatPolicy::validate`. Carries the logical length, so length
+// errors still take precedence over format errors.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub struct Violation {
+    pub logical_len: usize,
+    pub byte_offset: Option<usize>,
 }
 
 #[derive(Clone, Copy, Debug, PartialEq, Eq, Default)]
 pub struct AllowAll;
 impl FormatPolicy for AllowAll {
+    const CONST_FORMAT: ConstFormat = ConstFormat::AllowAll;
+
     #[inline(always)] fn check(_: &str) -> bool { true }
+
+    fn name() -> &'static str { "AllowAll" }
 }
 
 #[derive(Clone, Copy, Debug, PartialEq, Eq, Default)]
 pub struct AsciiOnly;
 impl FormatPolicy for AsciiOnly {
+    const CONST_FORMAT: ConstFormat = ConstFormat::AsciiOnly;
+
     #[inline(always)] fn check(s: &str) -> bool { s.is_ascii() }
+
+    // On ASCII input every built-in unit is one byte, so one scan does both.
+    fn validate<L: LengthPolicy>(s: &str) -> Result<usize, Violation> {
+        if s.is_ascii() {
+            return Ok(if L::CONST_LENGTH == ConstLength::Unsupported { L::logical_len(s) } else { s.len() });
+        }
+        Err(Violation { logical_len: L::logical_len(s), byte_offset: Self::invalid_at(s) })
+    }
+
+    fn invalid_at(s: &str) -> Option<usize> { s.bytes().position(|b| !b.is_ascii()) }
+
+    fn name() -> &'static str { "AsciiOnly" }
+
+    fn json_pattern() -> Option<&'static str> { Some("^[\\u0000-\\u007F]*$") }
 }
 
-#[derive(Debug, Clone, Copy, PartialEq, Eq)]
-pub enum BoundedStrError {
-    TooShort,
-    TooLong,
-    TooManyBytes,
-    InvalidContent,
-    MutationFailed,
+// Rejects interior NULs, so the content can be passed on as a C string.
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Default)]
+pub struct NoNul;
+impl FormatPolicy for NoNul {
+    const ACCEPTS_EMPTY: bool = true;
+
+    #[inline(always)] fn check(s: &str) -> bool { !s.as_bytes().contains(&0) }
+
+    fn invalid_at(s: &str) -> Option<usize> { s.bytes().position(|b| b == 0) }
+
+    fn name() -> &'static str { "NoNul" }
+
+    fn json_pattern() -> Option<&'static str> { Some("^[^\\u0000]*$") }
 }
 
-enum Storage<const MAX_BYTES: usize> {
-    Stack { buf: [u8; MAX_BYTES], len: usize },
-    #[cfg(feature = "alloc")]
-    Heap(Vec<u8>),
+// Rejects C0 and C1 control chars (tabs and line breaks included), so values
+// are safe in log lines and HTTP headers. `NoControlChars<false>` lets DEL through.
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Default)]
+pub struct NoControlChars<const REJECT_DEL: bool = true>;
+impl<const REJECT_DEL: bool> NoControlChars<REJECT_DEL> {
+    #[inline(always)]
+    fn rejects(c: char) -> bool { c.is_control() && (REJECT_DEL || c != '\x7F') }
 }
+impl<const REJECT_DEL: bool> FormatPolicy for NoControlChars<REJECT_DEL> {
+    const ACCEPTS_EMPTY: bool = true;
 
-impl<const MAX_BYTES: usize> Clone for Storage<MAX_BYTES> {
-    fn clone(&self) -> Self {
-        match self {
-            Self::Stack { buf, len } => Self::Stack { buf: *buf, len: *len },
-            #[cfg(feature = "alloc")]
-            Self::Heap(v) => Self::Heap(v.clone()),
-        }
+    #[inline(always)] fn check(s: &str) -> bool { !s.chars().any(Self::rejects) }
+
+    fn invalid_at(s: &str) -> Option<usize> { s.find(Self::rejects) }
+
+    fn name() -> &'static str { if REJECT_DEL { "NoControlChars" } else { "NoControlCharsAllowDel" } }
+
+    fn json_pattern() -> Option<&'static str> {
+        Some(if REJECT_DEL { "^[^\\u0000-\\u001F\\u007F-\\u009F]*$" } else { "^[^\\u0000-\\u001F\\u0080-\\u009F]*$" })
     }
 }
 
-pub struct BoundedStr<
-    const MIN: usize,
-    const MAX: usize,
-    const MAX_BYTES: usize,
-    L: LengthPolicy = Bytes,
-    F: FormatPolicy = AllowAll,
-	const Z: bool = false,
-> {
-    storage: Storage<MAX_BYTES>,
-    _marker: PhantomData<(L, F, core::convert::Infallible)>, 
+// Printable ASCII only: space through `~`.
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Default)]
+pub struct PrintableAscii;
+impl FormatPolicy for PrintableAscii {
+    const ACCEPTS_EMPTY: bool = true;
+
+    #[inline(always)] fn check(s: &str) -> bool { s.bytes().all(|b| matches!(b, b' '..=b'~')) }
+
+    fn validate<L: LengthPolicy>(s: &str) -> Result<usize, Violation> { validate_ascii::<L>(s, Self::invalid_at(s)) }
+
+    fn invalid_at(s: &str) -> Option<usize> { s.bytes().position(|b| !matches!(b, b' '..=b'~')) }
+
+    fn name() -> &'static str { "PrintableAscii" }
+
+    fn json_pattern() -> Option<&'static str> { Some("^[\\u0020-\\u007E]*$") }
 }
 
-impl<const MIN: usize, const MAX: usize, const MAX_BYTES: usize, L: LengthPolicy, F: FormatPolicy, const Z: bool>
-    BoundedStr<MIN, MAX, MAX_BYTES, L, F, Z>
-{
-    const _CHECK: () = {
-        assert!(MIN <= MAX, "MIN must be <= MAX");
-    };
+// ASCII letters and digits only, e.g. API tokens and order numbers.
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Default)]
+pub struct AsciiAlphanumeric;
+impl FormatPolicy for AsciiAlphanumeric {
+    const ACCEPTS_EMPTY: bool = true;
 
-    #[inline(always)]
-	pub fn len_bytes(&self) -> usize {
-        match &self.storage {
-            Storage::Stack { len, .. } => *len,
-            #[cfg(feature = "alloc")]
-            Storage::Heap(v) => v.len(),
-        }
-    }
+    #[inline(always)] fn check(s: &str) -> bool { s.bytes().all(|b| b.is_ascii_alphanumeric()) }
 
-    #[inline(always)]
-    pub fn len_logical(&self) -> usize {
-        L::logical_len(self.as_str())
-    }
+    fn validate<L: LengthPolicy>(s: &str) -> Result<usize, Violation> { validate_ascii::<L>(s, Self::invalid_at(s)) }
 
-    pub fn new(s: &str) -> Result<Self, BoundedStrError> {
-        let logical_len = L::logical_len(s);
-        if logical_len < MIN { return Err(BoundedStrError::TooShort); }
-        if logical_len > MAX { return Err(BoundedStrError::TooLong); }
-        if !F::check(s) { return Err(BoundedStrError::InvalidContent); }
+    fn invalid_at(s: &str) -> Option<usize> { s.bytes().position(|b| !b.is_ascii_alphanumeric()) }
 
-        let byte_len = s.len();
+    fn name() -> &'static str { "AsciiAlphanumeric" }
 
-        #[cfg(feature = "alloc")]
-        if byte_len > MAX_BYTES {
-            return Ok(Self {
-                storage: Storage::Heap(s.as_bytes().to_vec()),
-                _marker: PhantomData,
-            });
-        }
+    fn json_pattern() -> Option<&'static str> { Some("^[A-Za-z0-9]*$") }
+}
 
-        if byte_len > MAX_BYTES {
-            return Err(BoundedStrError::TooManyBytes);
-        }
+// A C-style identifier: an ASCII letter or `_`, then letters, digits or `_`.
+// The empty string passes; `MIN` decides whether it is allowed.
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Default)]
+pub struct Identifier;
+impl Identifier {
+    #[inline(always)]
+    fn accepts(i: usize, b: u8) -> bool { b == b'_' || if i == 0 { b.is_ascii_alphabetic() } else { b.is_ascii_alphanumeric() } }
+}
+impl FormatPolicy for Identifier {
+    const ACCEPTS_EMPTY: bool = true;
 
-        let mut buf = [0u8; MAX_BYTES];
-        buf[..byte_len].copy_from_slice(s.as_bytes());
-        Ok(Self {
-            storage: Storage::Stack { buf, len: byte_len },
-            _marker: PhantomData,
-        })
-    }
+    #[inline(always)] fn check(s: &str) -> bool { Self::invalid_at(s).is_none() }
 
-    pub fn mutate<Mut, R>(&mut self, mutator: Mut) -> Result<R, BoundedStrError>
-    where
-        Mut: FnOnce(&mut [u8], &mut usize) -> R, 
-    {
-        match &mut self.storage {
-            Storage::Stack { buf, len } => {
-                let mut temp_buf = *buf;
-                let mut temp_len = *len;
-                let res = mutator(&mut temp_buf, &mut temp_len);
-				
-                if temp_len > MAX_BYTES { return Err(BoundedStrError::TooManyBytes); }
-
-                if let Ok(s) = str::from_utf8(&temp_buf[..temp_len]) {
-                    let l_len = L::logical_len(s);
-                    
-                    if l_len >= MIN && l_len <= MAX && F::check(s) {
-                        *buf = temp_buf;
-                        *len = temp_len;
-                        return Ok(res);
-                    }
-                }
-                Err(BoundedStrError::MutationFailed)
-            }
+    fn validate<L: LengthPolicy>(s: &str) -> Result<usize, Violation> { validate_ascii::<L>(s, Self::invalid_at(s)) }
 
-            #[cfg(feature = "alloc")]            
-            Storage::Heap(v) => {
-                let mut temp_vec = v.clone();                
-                let limit = core::cmp::max(MAX, MAX_BYTES);
-                
-                let old_len = temp_vec.len();
+    fn invalid_at(s: &str) -> Option<usize> { s.bytes().enumerate().position(|(i, b)| !Self::accepts(i, b)) }
 
-                if temp_vec.len() < limit {
-                    temp_vec.resize(limit, 0); 
-                }
-                
-                let mut temp_len = old_len;
-                let res = mutator(&mut temp_vec, &mut temp_len);
+    fn name() -> &'static str { "Identifier" }
 
-                if temp_len > limit { 
-                    Self::clear_temp_vec::<Z>(&mut temp_vec);
-                    return Err(BoundedStrError::TooManyBytes); 
-                }
+    fn json_pattern() -> Option<&'static str> { Some("^([A-Za-z_][A-Za-z0-9_]*)?$") }
+}
 
-                temp_vec.truncate(temp_len);
-				
-                if let Ok(s) = str::from_utf8(&temp_vec) {
-                    let l_len = L::logical_len(s);
-                    if l_len >= MIN && l_len <= MAX && F::check(s) {
-                        *v = temp_vec;
-                        return Ok(res);
-                    }
-                }
+// Accepts only chars from a compile-time ASCII set, so one type covers hex,
+// base32, slugs or dial pads: `Charset<{ charset("0123456789abcdef") }>`.
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Default)]
+pub struct Charset<const ALLOWED: u128>;
+impl<const ALLOWED: u128> FormatPolicy for Charset<ALLOWED> {
+    const CONST_FORMAT: ConstFormat = ConstFormat::Charset(ALLOWED);
 
-                Self::clear_temp_vec::<Z>(&mut temp_vec);
-                Err(BoundedStrError::MutationFailed)
-            }
+    #[inline(always)] fn check(s: &str) -> bool { s.bytes().all(|b| in_charset(ALLOWED, b)) }
 
-        }
-    }
+    fn validate<L: LengthPolicy>(s: &str) -> Result<usize, Violation> { validate_ascii::<L>(s, Self::invalid_at(s)) }
 
-    #[inline(always)]
-	pub fn as_str(&self) -> &str {
-        match &self.storage {
-            Storage::Stack { buf, len } => unsafe { str::from_utf8_unchecked(&buf[..*len]) },
-            #[cfg(feature = "alloc")]
-            Storage::Heap(v) => unsafe { str::from_utf8_unchecked(v) },
-        }
+    fn invalid_at(s: &str) -> Option<usize> { s.bytes().position(|b| !in_charset(ALLOWED, b)) }
+}
+
+// The `Charset` mask allowing each char of `chars`. Panics (at compile time
+// inside a type) on non-ASCII chars.
+pub const fn charset(chars: &str) -> u128 {
+    let bytes = chars.as_bytes();
+    let mut mask = 0u128;
+    let mut i = 0;
+    while i < bytes.len() {
+        assert!(bytes[i].is_ascii(), "charset() only takes ASCII chars");
+        mask |= 1 << bytes[i];
+        i += 1;
     }
-	
-	#[inline(always)]
-    pub fn as_bytes(&self) -> &[u8] {
-        match &self.storage {
-            Storage::Stack { buf, len } => &buf[..*len],
-            #[cfg(feature = "alloc")]
-            Storage::Heap(v) => v.as_slice(),
-        }
+    mask
+}
+
+#[inline(always)]
+const fn in_charset(mask: u128, b: u8) -> bool { b < 128 && (mask >> b) & 1 == 1 }
+
+// `validate` for ASCII subsets, given the subset's `invalid_at(s)`: on ASCII
+// input every built-in unit is one byte, so that scan is the only one.
+fn validate_ascii<L: LengthPolicy>(s: &str, byte_offset: Option<usize>) -> Result<usize, Violation> {
+    match byte_offset {
+        None => Ok(if L::CONST_LENGTH == ConstLength::Unsupported { L::logical_len(s) } else { s.len() }),
+        byte_offset => Err(Violation { logical_len: L::logical_len(s), byte_offset }),
     }
-	
-	#[cfg(feature = "constant-time")]
-	#[inline(never)]
-    fn constant_time_eq(&self, other: &[u8]) -> bool {
-        let a = self.as_bytes();
-        let b = other;
+}
 
-        if a.len() != b.len() {
-            return false;
-        }
+// Accepts what both `A` and `B` accept, e.g. `And<AsciiOnly, NoNul>`.
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Default)]
+pub struct And<A, B>(PhantomData<(A, B)>);
+impl<A: FormatPolicy, B: FormatPolicy> FormatPolicy for And<A, B> {
+    const CONST_FORMAT: ConstFormat = match (A::CONST_FORMAT, B::CONST_FORMAT) {
+        (ConstFormat::AllowAll, f) | (f, ConstFormat::AllowAll) => f,
+        (ConstFormat::AsciiOnly, ConstFormat::AsciiOnly) => ConstFormat::AsciiOnly,
+        (ConstFormat::AsciiOnly, ConstFormat::Charset(m)) | (ConstFormat::Charset(m), ConstFormat::AsciiOnly) => ConstFormat::Charset(m),
+        (ConstFormat::Charset(a), ConstFormat::Charset(b)) => ConstFormat::Charset(a & b),
+        _ => ConstFormat::Unsupported,
+    };
+    const ACCEPTS_EMPTY: bool = A::ACCEPTS_EMPTY && B::ACCEPTS_EMPTY;
 
-        let mut result = 0u8;
-        for i in 0..a.len() {            
-            result |= a[i] ^ b[i];
+    #[inline(always)] fn check(s: &str) -> bool { A::check(s) && B::check(s) }
+
+    // The earlier of the two offenders, when both point at one.
+    fn invalid_at(s: &str) -> Option<usize> {
+        let a = if A::check(s) { None } else { A::invalid_at(s) };
+        let b = if B::check(s) { None } else { B::invalid_at(s) };
+        match (a, b) {
+            (Some(a), Some(b)) => Some(a.min(b)),
+            (a, b) => a.or(b),
         }
-        result == 0
     }
-	
-	#[inline(always)]
-    fn clear_temp_vec<const ZERO: bool>(v: &mut Vec<u8>) {
-        #[cfg(feature = "zeroize")]
-        if ZERO {
-            for byte in v.iter_mut() {
-                unsafe { core::ptr::write_volatile(byte, 0) };
-            }
-        }
+
+    // Keeps `A`'s single-pass measuring; `B` only checks.
+    fn validate<L: LengthPolicy>(s: &str) -> Result<usize, Violation> {
+        let logical_len = A::validate::<L>(s).map_err(|v| Violation { byte_offset: Self::invalid_at(s), ..v })?;
+        if B::check(s) { return Ok(logical_len); }
+        Err(Violation { logical_len, byte_offset: B::invalid_at(s) })
     }
 }
 
+// Accepts what either `A` or `B` accepts. A rejected string has no single
+// offending char, so errors carry no offset.
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Default)]
+pub struct Or<A, B>(PhantomData<(A, B)>);
+impl<A: FormatPolicy, B: FormatPolicy> FormatPolicy for Or<A, B> {
+    const CONST_FORMAT: ConstFormat = match (A::CONST_FORMAT, B::CONST_FORMAT) {
+        (ConstFormat::AllowAll, _) | (_, ConstFormat::AllowAll) => ConstFormat::AllowAll,
+        (ConstFormat::AsciiOnly, ConstFormat::AsciiOnly | ConstFormat::Charset(_))
+        | (ConstFormat::Charset(_), ConstFormat::AsciiOnly) => ConstFormat::AsciiOnly,
+        (ConstFormat::Charset(a), ConstFormat::Charset(b)) => ConstFormat::Charset(a | b),
+        _ => ConstFormat::Unsupported,
+    };
+    const ACCEPTS_EMPTY: bool = A::ACCEPTS_EMPTY || B::ACCEPTS_EMPTY;
 
-impl<const MIN: usize, const MAX: usize, const MAX_BYTES: usize, L: LengthPolicy, F: FormatPolicy, const Z: bool>
-    PartialEq for BoundedStr<MIN, MAX, MAX_BYTES, L, F, Z>
-{
-    fn eq(&self, other: &Self) -> bool {
-        #[cfg(feature = "constant-time")]
-        {
-            self.constant_time_eq(other.as_bytes())
-        }
-        #[cfg(not(feature = "constant-time"))]
-        {
-            self.as_str() == other.as_str()
-        }
-    }
+    #[inline(always)] fn check(s: &str) -> bool { A::check(s) || B::check(s) }
 }
 
-impl<const MIN: usize, const MAX: usize, const MAX_BYTES: usize, L: LengthPolicy, F: FormatPolicy, const Z: bool> 
-    Clone for BoundedStr<MIN, MAX, MAX_BYTES, L, F, Z> {
-    fn clone(&self) -> Self {
-        Self { storage: self.storage.clone(), _marker: PhantomData }
-    }
+// Accepts exactly what `A` rejects, e.g. `And<AsciiOnly, Not<Reserved>>`.
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Default)]
+pub struct Not<A>(PhantomData<A>);
+impl<A: FormatPolicy> FormatPolicy for Not<A> {
+    #[inline(always)] fn check(s: &str) -> bool { !A::check(s) }
 }
 
-impl<const MIN: usize, const MAX: usize, const MAX_BYTES: usize, L: LengthPolicy, F: FormatPolicy, const Z: bool>
-    Eq for BoundedStr<MIN, MAX, MAX_BYTES, L, F, Z> {}
-	
-impl<const MIN: usize, const MAX: usize, const MAX_BYTES: usize, L: LengthPolicy, F: FormatPolicy, const Z: bool>
-    PartialEq<&str> for BoundedStr<MIN, MAX, MAX_BYTES, L, F, Z>
-{
-    fn eq(&self, other: &&str) -> bool { self.as_str() == *other }
+// A regex for `RegexPolicy`, usually declared with `regex_policy!`.
+#[cfg(feature = "regex-lite")]
+pub trait RegexPattern {
+    // Anchored (`^(?:...)$`), so it must match the whole string.
+    const PATTERN: &'static str;
+
+    // `PATTERN` compiled once per process.
+    fn regex() -> &'static regex_lite::Regex;
 }
 
-impl<const MIN: usize, const MAX: usize, const MAX_BYTES: usize, L: LengthPolicy, F: FormatPolicy, const Z: bool> 
-    Deref for BoundedStr<MIN, MAX, MAX_BYTES, L, F, Z> {
-    type Target = str;
-    fn deref(&self) -> &str { self.as_str() }
+// Accepts strings matching `P::PATTERN`. A rejected string has no single
+// offending char, so errors carry no offset.
+#[cfg(feature = "regex-lite")]
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Default)]
+pub struct RegexPolicy<P>(PhantomData<P>);
+#[cfg(feature = "regex-lite")]
+impl<P: RegexPattern> FormatPolicy for RegexPolicy<P> {
+    #[inline(always)] fn check(s: &str) -> bool { P::regex().is_match(s) }
+
+    fn json_pattern() -> Option<&'static str> { Some(P::PATTERN) }
 }
 
-impl<const MIN: usize, const MAX: usize, const MAX_BYTES: usize, L: LengthPolicy, F: FormatPolicy, const Z: bool>
-    TryFrom<&str> for BoundedStr<MIN, MAX, MAX_BYTES, L, F, Z>
-{
-    type Error = BoundedStrError;
-    fn try_from(s: &str) -> Result<Self, Self::Error> { Self::new(s) }
+// How `==` compares content and what `Hash` feeds the hasher. Equality between
+// two types is constant-time if either side asks for it.
+pub trait EqPolicy {
+    const CONSTANT_TIME: bool;
+
+    fn hash<H: Hasher>(s: &str, state: &mut H) { s.hash(state) }
 }
 
-impl<const MIN: usize, const MAX: usize, const MAX_BYTES: usize, L: LengthPolicy, F: FormatPolicy, const Z: bool>
-    FromStr for BoundedStr<MIN, MAX, MAX_BYTES, L, F, Z>
-{
-    type Err = BoundedStrError;
-    fn from_str(s: &str) -> Result<Self, Self::Err> { Self::new(s) }
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Default)]
+pub struct FastEq;
+impl EqPolicy for FastEq {
+    const CONSTANT_TIME: bool = false;
 }
 
-impl<const MIN: usize, const MAX: usize, const MAX_BYTES: usize, L: LengthPolicy, F: FormatPolicy, const Z: bool>
-    Hash for BoundedStr<MIN, MAX, MAX_BYTES, L, F, Z>
-{
-    fn hash<H: Hasher>(&self, state: &mut H) { self.as_str().hash(state) }
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Default)]
+pub struct ConstantTime;
+impl EqPolicy for ConstantTime {
+    const CONSTANT_TIME: bool = true;
 }
 
-impl<const MIN: usize, const MAX: usize, const MAX_BYTES: usize, L: LengthPolicy, F: FormatPolicy, const Z: bool>
-    Display for BoundedStr<MIN, MAX, MAX_BYTES, L, F, Z>
-{
-    fn fmt(&self, f: &mut Formatter<'_>) -> fmt::Result { f.write_str(self.as_str()) }
+// Secret key for `KeyedHash`, e.g. drawn from the OS RNG once per process.
+pub trait HashKey {
+    fn key() -> (u64, u64);
 }
 
-impl<const MIN: usize, const MAX: usize, const MAX_BYTES: usize, L: LengthPolicy, F: FormatPolicy, const Z: bool>
-    fmt::Debug for BoundedStr<MIN, MAX, MAX_BYTES, L, F, Z>
-{
-    fn fmt(&self, f: &mut Formatter<'_>) -> fmt::Result {
-        f.debug_struct("BoundedStr")
-            .field("value", &self.as_str())
-            .field("len_bytes", &self.len_bytes())
-            .field("len_logical", &self.len_logical())
-            .finish()
+// Constant-time `==`, and `Hash` only sees a SipHash digest keyed with
+// `K::key()`, so secrets can be map keys without reaching the hasher.
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Default)]
+pub struct KeyedHash<K>(PhantomData<K>);
+impl<K: HashKey> EqPolicy for KeyedHash<K> {
+    const CONSTANT_TIME: bool = true;
+
+    fn hash<H: Hasher>(s: &str, state: &mut H) {
+        let (k0, k1) = K::key();
+        let mut sip = SipHasher24::new(k0, k1);
+        s.hash(&mut sip);
+        state.write_u64(sip.finish());
     }
 }
 
-impl<const MIN: usize, const MAX: usize, const MAX_BYTES: usize, L: LengthPolicy, F: FormatPolicy, const Z: bool> 
-    Drop for BoundedStr<MIN, MAX, MAX_BYTES, L, F, Z> 
-{
+// SipHash-2-4, the keyed PRF behind `KeyedHash`. Vendored because
+// `core::hash::SipHasher` is deprecated and `RandomState` needs `std`.
+struct SipHasher24 {
+    v: [u64; 4],
+    tail: u64,
+    len: usize,
+}
+
+impl SipHasher24 {
+    fn new(k0: u64, k1: u64) -> Self {
+        Self {
+            v: [k0 ^ 0x736f_6d65_7073_6575, k1 ^ 0x646f_7261_6e64_6f6d, k0 ^ 0x6c79_6765_6e65_7261, k1 ^ 0x7465_6462_7974_6573],
+            tail: 0,
+            len: 0,
+        }
+    }
+
     #[inline(always)]
-    fn drop(&mut self) {
-        #[cfg(feature = "zeroize")]
-        if Z {
-            match &mut self.storage {
-                Storage::Stack { buf, .. } => {
-                    for byte in buf.iter_mut() {
-                        unsafe { core::ptr::write_volatile(byte, 0) };
-                    }
-                }
-                #[cfg(feature = "alloc")]
-                Storage::Heap(v) => {
-                    for byte in v.iter_mut() {
-                        unsafe { core::ptr::write_volatile(byte, 0) };
-                    }
-                }
-            }
+    fn rounds(&mut self, n: usize) {
+        let [v0, v1, v2, v3] = &mut self.v;
+        for _ in 0..n {
+            *v0 = v0.wrapping_add(*v1); *v1 = v1.rotate_left(13); *v1 ^= *v0; *v0 = v0.rotate_left(32);
+            *v2 = v2.wrapping_add(*v3); *v3 = v3.rotate_left(16); *v3 ^= *v2;
+            *v0 = v0.wrapping_add(*v3); *v3 = v3.rotate_left(21); *v3 ^= *v0;
+            *v2 = v2.wrapping_add(*v1); *v1 = v1.rotate_left(17); *v1 ^= *v2; *v2 = v2.rotate_left(32);
         }
     }
+
+    #[inline(always)]
+    fn compress(&mut self, m: u64) {
+        self.v[3] ^= m;
+        self.rounds(2);
+        self.v[0] ^= m;
+    }
 }
 
+impl Hasher for SipHasher24 {
+    fn write(&mut self, bytes: &[u8]) {
+        for &b in bytes {
+            self.tail |= u64::from(b) << (8 * (self.len % 8));
+            self.len = self.len.wrapping_add(1);
+            if self.len.is_multiple_of(8) {
+                self.compress(self.tail);
+                self.tail = 0;
+            }
+        }
+    }
 
-#[cfg(feature = "serde")]
-impl<'de, const MIN: usize, const MAX: usize, const MAX_BYTES: usize, L: LengthPolicy, F: FormatPolicy, const Z: bool> 
-    serde::Deserialize<'de> for BoundedStr<MIN, MAX, MAX_BYTES, L, F, Z> 
-{
-    fn deserialize<D>(deserializer: D) -> Result<Self, D::Error>
-    where
-        D: serde::Deserializer<'de>,
-    {
-        let s = <&str>::deserialize(deserializer)?;
-        
-        Self::new(s).map_err(|e| {
-            serde::de::Error::custom(match e {
-                BoundedStrError::TooShort => "string too short",
-                BoundedStrError::TooLong => "string too long",
-                BoundedStrError::TooManyBytes => "too many bytes for buffer",
-                BoundedStrError::InvalidContent => "invalid content format",
-                BoundedStrError::MutationFailed => "mutation failed",
-            })
-        })
+    fn finish(&self) -> u64 {
+        let mut last = Self { v: self.v, tail: 0, len: 0 };
+        last.compress(((self.len as u64) << 56) | self.tail);
+        last.v[2] ^= 0xff;
+        last.rounds(4);
+        let [v0, v1, v2, v3] = last.v;
+        v0 ^ v1 ^ v2 ^ v3
     }
 }
 
-#[cfg(feature = "serde")]
-impl<const MIN: usize, const MAX: usize, const MAX_BYTES: usize, L: LengthPolicy, F: FormatPolicy, const Z: bool> 
-    serde::Serialize for BoundedStr<MIN, MAX, MAX_BYTES, L, F, Z> 
-{
-    fn serialize<S>(&self, serializer: S) -> Result<S::Ok, S::Error>
-    where
-        S: serde::Serializer,
-    {
-        serializer.serialize_str(self.as_str())
-    }
+// What `Display`/`Debug` print in place of a zeroizing (`Z = true`) value.
+pub const REDACTED: &str = "***REDACTED***";
+
+// Where `new_padded()`/`pad_to_min()` put the fill; `Center` puts the odd one on the right.
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash)]
+pub enum PadSide {
+    Left,
+    Right,
+    Center,
 }
 
+// Marker: every string accepted by `Self` is also accepted by `F2`.
+pub trait Refines<F2: FormatPolicy>: FormatPolicy {}
 
-pub type StackStr<const MIN: usize, const MAX: usize, const MAXB: usize = MAX, L = Bytes, F = AllowAll, const Z: bool = false > = BoundedStr<MIN, MAX, MAXB, L, F, Z>;
+impl<F: FormatPolicy> Refines<AllowAll> for F {}
+impl Refines<AsciiOnly> for AsciiOnly {}
+impl Refines<NoNul> for NoNul {}
+impl<const D: bool> Refines<NoNul> for NoControlChars<D> {}
+impl<const D: bool> Refines<NoControlChars<false>> for NoControlChars<D> {}
+impl Refines<NoControlChars<true>> for NoControlChars<true> {}
+impl Refines<PrintableAscii> for PrintableAscii {}
+impl Refines<AsciiOnly> for PrintableAscii {}
+impl Refines<NoNul> for PrintableAscii {}
+impl<const D: bool> Refines<NoControlChars<D>> for PrintableAscii {}
+impl<const M: u128> Refines<Charset<M>> for Charset<M> {}
+impl<const M: u128> Refines<AsciiOnly> for Charset<M> {}
+impl Refines<AsciiAlphanumeric> for AsciiAlphanumeric {}
+impl Refines<AsciiOnly> for AsciiAlphanumeric {}
+impl Refines<PrintableAscii> for AsciiAlphanumeric {}
+impl Refines<NoNul> for AsciiAlphanumeric {}
+impl<const D: bool> Refines<NoControlChars<D>> for AsciiAlphanumeric {}
+impl Refines<Identifier> for Identifier {}
+impl Refines<AsciiOnly> for Identifier {}
+impl Refines<PrintableAscii> for Identifier {}
+impl Refines<NoNul> for Identifier {}
+impl<const D: bool> Refines<NoControlChars<D>> for Identifier {}
+impl<A: FormatPolicy, B: FormatPolicy> Refines<And<A, B>> for And<A, B> {}
+impl<A: FormatPolicy, B: FormatPolicy> Refines<Or<A, B>> for Or<A, B> {}
+impl<A: FormatPolicy> Refines<Not<A>> for Not<A> {}
+#[cfg(feature = "regex-lite")]
+impl<P: RegexPattern> Refines<RegexPolicy<P>> for RegexPolicy<P> {}
 
-#[cfg(feature = "alloc")]
-pub type FlexStr<const MIN: usize, const MAX: usize, const MAXB: usize = 4096, L = Bytes, F = AllowAll, const Z: bool = false > = BoundedStr<MIN, MAX, MAXB, L, F, Z>;
\ No newline at end of file
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+#[non_exhaustive]
+pub enum BoundedStrError {
+    TooShort { actual: usize, min: usize },
+    TooLong { actual: usize, max: usize },
+    TooManyBytes { actual: usize, max_bytes: usize },
+    InvalidContent { byte_offset: usize, char: Option<char> },
+    MutationFailed { kind: MutationErrorKind },
+    NotCharBoundary,
+    InvalidUtf8,
+    InvalidUtf16,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+#[non_exhaustive]
+pub enum MutationErrorKind {
+    InvalidUtf8,
+    LengthViolation,
+    PolicyViolation,
+}
+
+impl MutationErrorKind {
+    const fn message(&self) -> &'static str {
+        match self {
+            Self::InvalidUtf8 => "invalid utf-8",
+            Self::LengthViolation => "length out of bounds",
+            Self::PolicyViolation => "format policy violated",
+        }
+    }
+}
+
+impl BoundedStrError {
+    fn invalid_content<F: FormatPolicy>(s: &str) -> Self {
+        Self::invalid_content_at(s, F::invalid_at(s))
+    }
+
+    fn invalid_content_at(s: &str, byte_offset: Option<usize>) -> Self {
+        match byte_offset {
+            Some(byte_offset) => Self::InvalidContent {
+                byte_offset,
+                char: s.get(byte_offset..).and_then(|rest| rest.chars().next()),
+            },
+            None => Self::InvalidContent { byte_offset: 0, char: None },
+        }
+    }
+
+    const fn message(&self) -> &'static str {
+        match self {
+            Self::TooShort { .. } => "string too short",
+            Self::TooLong { .. } => "string too long",
+            Self::TooManyBytes { .. } => "too many bytes for buffer",
+            Self::InvalidContent { .. } => "invalid content format",
+            Self::MutationFailed { .. } => "mutation failed",
+            Self::NotCharBoundary => "index is not on a char boundary",
+            Self::InvalidUtf8 => "invalid utf-8",
+            Self::InvalidUtf16 => "invalid utf-16",
+        }
+    }
+}
+
+impl Display for BoundedStrError {
+    fn fmt(&self, f: &mut Formatter<'_>) -> fmt::Result {
+        match self {
+            Self::TooShort { actual, min } => write!(f, "{} (length {}, min {})", self.message(), actual, min),
+            Self::TooLong { actual, max } => write!(f, "{} (length {}, max {})", self.message(), actual, max),
+            Self::TooManyBytes { actual, max_bytes } => write!(f, "{} ({} bytes, max {})", self.message(), actual, max_bytes),
+            Self::InvalidContent { byte_offset, char: Some(c) } => write!(f, "{} ({:?} at byte {})", self.message(), c, byte_offset),
+            Self::MutationFailed { kind } => write!(f, "{}: {}", self.message(), kind.message()),
+            _ => f.write_str(self.message()),
+        }
+    }
+}
+
+impl core::error::Error for BoundedStrError {}
+
+// The inline length in one byte, whatever `MAX_BYTES` is, so small buffers
+// pack densely. Never zero, which leaves a niche: `Option<BoundedStr>` costs
+// nothing even without `alloc`, where `Storage` has no second variant to borrow one from.
+//   1..=127    `len + 1`, for short content;
+//   128..=254  `128 + (MAX_BYTES - len)`, for nearly full buffers;
+//   255        neither, so at least 127 bytes are unused and `len` is kept
+//              in the last `usize` of the buffer.
+#[derive(Clone, Copy)]
+struct InlineLen(NonZero<u8>);
+
+const TAIL_LEN: usize = size_of::<usize>();
+
+impl InlineLen {
+    #[inline(always)]
+    const fn new<const N: usize>(len: usize, buf: &mut [MaybeUninit<u8>; N]) -> Self {
+        let tag = if len < 127 {
+            len + 1
+        } else if N - len < 127 {
+            128 + (N - len)
+        } else {
+            let bytes = len.to_ne_bytes();
+            let mut i = 0;
+            while i < TAIL_LEN {
+                buf[N - TAIL_LEN + i] = MaybeUninit::new(bytes[i]);
+                i += 1;
+            }
+            255
+        };
+        match NonZero::new(tag as u8) {
+            Some(n) => Self(n),
+            None => unreachable!(),
+        }
+    }
+
+    #[inline(always)]
+    const fn get<const N: usize>(self, buf: &[MaybeUninit<u8>; N]) -> usize {
+        match self.0.get() {
+            tag @ 1..=127 => tag as usize - 1,
+            tag @ 128..=254 => N - (tag as usize - 128),
+            _ => {
+                let mut bytes = [0; TAIL_LEN];
+                let mut i = 0;
+                while i < TAIL_LEN {
+                    bytes[i] = unsafe { buf[N - TAIL_LEN + i].assume_init() };
+                    i += 1;
+                }
+                usize::from_ne_bytes(bytes)
+            }
+        }
+    }
+}
+
+// An out-of-line buffer with the cached `L::logical_len` of its content.
+// Inline values are short enough to measure instead, so only this variant
+// pays for the cache, and boxing it keeps `Storage` one pointer wide.
+#[cfg(feature = "alloc")]
+#[derive(Clone)]
+struct Spilled<B> {
+    bytes: B,
+    // `None` until the owning `BoundedStr` sets it.
+    logical_len: Option<usize>,
+}
+
+#[cfg(feature = "alloc")]
+impl<B> Spilled<B> {
+    #[inline(always)]
+    fn new(bytes: B) -> alloc::boxed::Box<Self> {
+        alloc::boxed::Box::new(Self { bytes, logical_len: None })
+    }
+}
+
+#[cfg(feature = "alloc")]
+impl<B> Deref for Spilled<B> {
+    type Target = B;
+
+    #[inline(always)]
+    fn deref(&self) -> &B { &self.bytes }
+}
+
+#[cfg(feature = "alloc")]
+impl<B> core::ops::DerefMut for Spilled<B> {
+    #[inline(always)]
+    fn deref_mut(&mut self) -> &mut B { &mut self.bytes }
+}
+
+// Only `buf[..len]` is initialized, so construction writes just the used prefix.
+enum Storage<const MAX_BYTES: usize> {
+    Stack { buf: [MaybeUninit<u8>; MAX_BYTES], len: InlineLen },
+    #[cfg(feature = "alloc")]
+    Heap(alloc::boxed::Box<Spilled<Vec<u8>>>),
+    // A received buffer kept as-is; copied out on the first write.
+    #[cfg(feature = "bytes")]
+    Shared(alloc::boxed::Box<Spilled<bytes::Bytes>>),
+}
+
+impl<const MAX_BYTES: usize> Storage<MAX_BYTES> {
+    const UNINIT: [MaybeUninit<u8>; MAX_BYTES] = [MaybeUninit::uninit(); MAX_BYTES];
+
+    #[inline(always)]
+    fn empty() -> Self {
+        Self::stack(&[])
+    }
+
+    // `bytes.len()` must not exceed `MAX_BYTES`.
+    #[inline(always)]
+    fn stack(bytes: &[u8]) -> Self {
+        let mut buf = Self::UNINIT;
+        init_prefix(&mut buf, bytes);
+        let len = InlineLen::new(bytes.len(), &mut buf);
+        Self::Stack { buf, len }
+    }
+
+    #[cfg(feature = "alloc")]
+    #[inline(always)]
+    fn heap(v: Vec<u8>) -> Self {
+        Self::Heap(Spilled::new(v))
+    }
+
+    #[inline(always)]
+    fn cached_logical_len(&self) -> Option<usize> {
+        match self {
+            Self::Stack { .. } => None,
+            #[cfg(feature = "alloc")]
+            Self::Heap(h) => h.logical_len,
+            #[cfg(feature = "bytes")]
+            Self::Shared(b) => b.logical_len,
+        }
+    }
+
+    #[inline(always)]
+    fn set_logical_len(&mut self, n: usize) {
+        match self {
+            Self::Stack { .. } => { let _ = n; }
+            #[cfg(feature = "alloc")]
+            Self::Heap(h) => h.logical_len = Some(n),
+            #[cfg(feature = "bytes")]
+            Self::Shared(b) => b.logical_len = Some(n),
+        }
+    }
+
+    #[inline(always)]
+    fn as_bytes(&self) -> &[u8] {
+        match self {
+            Self::Stack { buf, len } => unsafe { assume_init(&buf[..len.get(buf)]) },
+            #[cfg(feature = "alloc")]
+            Self::Heap(v) => v.as_slice(),
+            #[cfg(feature = "bytes")]
+            Self::Shared(b) => &b.bytes,
+        }
+    }
+
+    #[inline(always)]
+    fn is_heap(&self) -> bool {
+        match self {
+            Self::Stack { .. } => false,
+            #[cfg(feature = "alloc")]
+            Self::Heap(_) => true,
+            #[cfg(feature = "bytes")]
+            Self::Shared(_) => true,
+        }
+    }
+
+    #[inline(always)]
+    fn capacity(&self) -> usize {
+        match self {
+            Self::Stack { .. } => MAX_BYTES,
+            #[cfg(feature = "alloc")]
+            Self::Heap(v) => v.capacity(),
+            #[cfg(feature = "bytes")]
+            Self::Shared(b) => b.len(),
+        }
+    }
+
+    #[inline(always)]
+    fn as_mut_bytes(&mut self) -> &mut [u8] {
+        self.unshare();
+        match self {
+            Self::Stack { buf, len } => {
+                let len = len.get(buf);
+                unsafe { assume_init_mut(&mut buf[..len]) }
+            }
+            #[cfg(feature = "alloc")]
+            Self::Heap(v) => v.as_mut_slice(),
+            #[cfg(feature = "bytes")]
+            Self::Shared(_) => unreachable!(),
+        }
+    }
+
+    // Readies the storage for a closure edit: a shared buffer is copied out, and
+    // a value with no inline capacity moves to the heap so the edit can grow it.
+    #[inline(always)]
+    fn make_mut<const ZERO: bool>(&mut self) {
+        #[cfg(feature = "alloc")]
+        if MAX_BYTES == 0 { self.spill::<ZERO>(0); }
+        self.unshare();
+    }
+
+    // Turns a `Shared` buffer into an owned `Heap` one. `Vec::from` reuses the
+    // allocation when this is the only handle to a `Vec`-backed `Bytes`.
+    #[inline(always)]
+    fn unshare(&mut self) {
+        #[cfg(feature = "bytes")]
+        if let Self::Shared(b) = self {
+            let logical_len = b.logical_len;
+            *self = Self::Heap(alloc::boxed::Box::new(Spilled { bytes: Vec::from(core::mem::take(&mut b.bytes)), logical_len }));
+        }
+    }
+
+    fn retain<const ZERO: bool>(&mut self, mut keep: impl FnMut(char) -> bool) {
+        let bytes = self.as_mut_bytes();
+        let len = bytes.len();
+        let (mut read, mut write) = (0, 0);
+        while read < len {
+            let c = match unsafe { str::from_utf8_unchecked(&bytes[read..]) }.chars().next() {
+                Some(c) => c,
+                None => break,
+            };
+            let n = c.len_utf8();
+            if keep(c) {
+                bytes.copy_within(read..read + n, write);
+                write += n;
+            }
+            read += n;
+        }
+        let _ = self.splice::<ZERO>(write..len, &[]);
+    }
+
+    fn splice<const ZERO: bool>(&mut self, range: Range<usize>, with: &[u8]) -> Result<(), BoundedStrError> {
+        let old_len = self.as_bytes().len();
+        let new_len = old_len - range.len() + with.len();
+        match self {
+            Self::Stack { buf, len } if new_len <= MAX_BYTES => {
+                buf.copy_within(range.end..old_len, range.start + with.len());
+                init_prefix(&mut buf[range.start..], with);
+                if new_len < old_len {
+                    wipe::<ZERO>(unsafe { assume_init_mut(&mut buf[new_len..old_len]) });
+                }
+                *len = InlineLen::new(new_len, buf);
+            }
+            #[cfg(feature = "alloc")]
+            Self::Stack { buf, .. } => {
+                let mut v = Vec::with_capacity(new_len);
+                v.extend_from_slice(unsafe { assume_init(&buf[..range.start]) });
+                v.extend_from_slice(with);
+                v.extend_from_slice(unsafe { assume_init(&buf[range.end..old_len]) });
+                wipe_uninit::<ZERO>(buf);
+                lock_vec::<ZERO>(&v);
+                *self = Self::heap(v);
+            }
+            #[cfg(not(feature = "alloc"))]
+            Self::Stack { .. } => return Err(BoundedStrError::TooManyBytes { actual: new_len, max_bytes: MAX_BYTES }),
+            #[cfg(feature = "alloc")]
+            Self::Heap(v) => {
+                if new_len < old_len {
+                    v.copy_within(range.end..old_len, range.start + with.len());
+                    v[range.start..range.start + with.len()].copy_from_slice(with);
+                    wipe::<ZERO>(&mut v[new_len..old_len]);
+                    v.truncate(new_len);
+                } else {
+                    if new_len > v.capacity() {
+                        let capacity = core::cmp::max(new_len, v.capacity() * 2);
+                        grow_vec::<ZERO>(v, capacity);
+                    }
+                    v.splice(range, with.iter().copied());
+                }
+            }
+            #[cfg(feature = "bytes")]
+            Self::Shared(b) => {
+                let mut v = Vec::with_capacity(new_len);
+                v.extend_from_slice(&b[..range.start]);
+                v.extend_from_slice(with);
+                v.extend_from_slice(&b[range.end..]);
+                *self = Self::heap(v);
+            }
+        }
+        Ok(())
+    }
+
+    #[inline(always)]
+    fn push<const ZERO: bool>(&mut self, bytes: &[u8]) -> Result<(), BoundedStrError> {
+        let end = self.as_bytes().len();
+        self.splice::<ZERO>(end..end, bytes)
+    }
+
+    #[inline(always)]
+    fn wipe<const ZERO: bool>(&mut self) {
+        match self {
+            Self::Stack { buf, .. } => wipe_uninit::<ZERO>(buf),
+            #[cfg(feature = "alloc")]
+            Self::Heap(v) => release_vec::<ZERO>(v),
+            // Other handles may still read it; it is released on drop.
+            #[cfg(feature = "bytes")]
+            Self::Shared(_) => {}
+        }
+    }
+
+    // Moves heap content back into the inline buffer if it fits.
+    fn inline<const ZERO: bool>(&mut self) -> bool {
+        if self.is_heap() {
+            let v = self.as_bytes();
+            if v.len() > MAX_BYTES { return false; }
+            let mut stack = Self::stack(v);
+            core::mem::swap(self, &mut stack);
+            stack.wipe::<ZERO>();
+        }
+        true
+    }
+
+    // Moves inline content to a heap buffer of at least `capacity` bytes.
+    #[cfg(feature = "alloc")]
+    fn spill<const ZERO: bool>(&mut self, capacity: usize) {
+        self.unshare();
+        if let Self::Stack { buf, len } = self {
+            let len = len.get(buf);
+            let mut v = Vec::with_capacity(core::cmp::max(capacity, len));
+            lock_vec::<ZERO>(&v);
+            v.extend_from_slice(unsafe { assume_init(&buf[..len]) });
+            wipe_uninit::<ZERO>(buf);
+            *self = Self::heap(v);
+        }
+    }
+
+    #[cfg(feature = "alloc")]
+    fn reserve<const ZERO: bool>(&mut self, additional: usize) {
+        let capacity = self.as_bytes().len().saturating_add(additional);
+        self.unshare();
+        match self {
+            Self::Stack { .. } if capacity <= MAX_BYTES => {}
+            Self::Stack { .. } => self.spill::<ZERO>(capacity),
+            Self::Heap(v) => grow_vec::<ZERO>(v, capacity),
+            #[cfg(feature = "bytes")]
+            Self::Shared(_) => unreachable!(),
+        }
+    }
+
+    #[inline(always)]
+    fn lock<const ZERO: bool>(&self) {
+        #[cfg(feature = "alloc")]
+        if let Self::Heap(v) = self { lock_vec::<ZERO>(v); }
+    }
+}
+
+// `Σ` at `i` ends a word: a cased letter comes before it and none follows,
+// skipping apostrophes and similar in-word punctuation.
+#[cfg(not(feature = "alloc"))]
+fn is_word_final_sigma(s: &str, i: usize) -> bool {
+    let ignorable = |c: &char| matches!(c, '\'' | '.' | ':' | '\u{AD}' | '\u{B7}' | '\u{2019}');
+    let cased = |c: char| c.is_lowercase() || c.is_uppercase();
+    let before = s[..i].chars().rev().find(|c| !ignorable(c)).is_some_and(cased);
+    let after = s[i + 'Σ'.len_utf8()..].chars().find(|c| !ignorable(c)).is_some_and(cased);
+    before && !after
+}
+
+#[inline(always)]
+fn wipe<const ZERO: bool>(bytes: &mut [u8]) {
+    #[cfg(feature = "zeroize")]
+    if ZERO { zeroize::Zeroize::zeroize(bytes); }
+    #[cfg(not(feature = "zeroize"))]
+    let _ = bytes;
+}
+
+// Wipes a whole stack buffer, including the never-initialized tail.
+#[inline(always)]
+fn wipe_uninit<const ZERO: bool>(bytes: &mut [MaybeUninit<u8>]) {
+    #[cfg(feature = "zeroize")]
+    if ZERO { zeroize::Zeroize::zeroize(bytes); }
+    #[cfg(not(feature = "zeroize"))]
+    let _ = bytes;
+}
+
+// Callers guarantee that every byte of `bytes` was written.
+#[inline(always)]
+unsafe fn assume_init(bytes: &[MaybeUninit<u8>]) -> &[u8] {
+    unsafe { core::slice::from_raw_parts(bytes.as_ptr().cast(), bytes.len()) }
+}
+
+#[inline(always)]
+unsafe fn assume_init_mut(bytes: &mut [MaybeUninit<u8>]) -> &mut [u8] {
+    unsafe { core::slice::from_raw_parts_mut(bytes.as_mut_ptr().cast(), bytes.len()) }
+}
+
+#[inline(always)]
+fn init_prefix(buf: &mut [MaybeUninit<u8>], bytes: &[u8]) {
+    let src = unsafe { core::slice::from_raw_parts(bytes.as_ptr().cast::<MaybeUninit<u8>>(), bytes.len()) };
+    buf[..bytes.len()].copy_from_slice(src);
+}
+
+// Zero-fills the unused tail so a closure can be handed the whole buffer.
+#[inline(always)]
+fn init_tail<const N: usize>(buf: &mut [MaybeUninit<u8>; N], len: usize) -> &mut [u8; N] {
+    buf[len..].fill(MaybeUninit::new(0));
+    unsafe { &mut *(buf as *mut [MaybeUninit<u8>; N]).cast::<[u8; N]>() }
+}
+
+// Also wipes the spare capacity, for buffers that are about to be freed.
+#[cfg(feature = "alloc")]
+#[cfg_attr(not(feature = "zeroize"), allow(clippy::ptr_arg))]
+fn wipe_vec<const ZERO: bool>(v: &mut Vec<u8>) {
+    wipe::<ZERO>(v);
+    #[cfg(feature = "zeroize")]
+    if ZERO { zeroize::Zeroize::zeroize(v.spare_capacity_mut()); }
+}
+
+// Page-locks a heap buffer and keeps it out of core dumps (`mlock` feature).
+// Best effort: failures, e.g. from `RLIMIT_MEMLOCK`, are ignored.
+#[cfg(feature = "alloc")]
+#[inline(always)]
+fn lock_vec<const ZERO: bool>(v: &Vec<u8>) {
+    #[cfg(feature = "mlock")]
+    if ZERO && v.capacity() > 0 {
+        unsafe { memsec::mlock(v.as_ptr().cast_mut(), v.capacity()) };
+    }
+    #[cfg(not(feature = "mlock"))]
+    let _ = v;
+}
+
+// Wipes a buffer that is about to be freed or handed out and undoes `lock_vec`.
+#[cfg(feature = "alloc")]
+#[cfg_attr(not(any(feature = "zeroize", feature = "mlock")), allow(clippy::ptr_arg))]
+fn release_vec<const ZERO: bool>(v: &mut Vec<u8>) {
+    wipe_vec::<ZERO>(v);
+    #[cfg(feature = "mlock")]
+    if ZERO && v.capacity() > 0 {
+        unsafe { memsec::munlock(v.as_mut_ptr(), v.capacity()) };
+    }
+}
+
+// Grows `v` to at least `capacity`. Zeroizing buffers are moved by hand so the
+// old allocation is wiped and unlocked before it is freed.
+#[cfg(feature = "alloc")]
+fn grow_vec<const ZERO: bool>(v: &mut Vec<u8>, capacity: usize) {
+    if capacity <= v.capacity() { return; }
+    if ZERO && cfg!(any(feature = "zeroize", feature = "mlock")) {
+        move_vec::<ZERO>(v, capacity);
+    } else {
+        v.reserve_exact(capacity - v.len());
+    }
+}
+
+// Drops spare capacity, moving zeroizing buffers by hand like `grow_vec`.
+#[cfg(feature = "alloc")]
+fn shrink_vec<const ZERO: bool>(v: &mut Vec<u8>) {
+    if v.capacity() == v.len() { return; }
+    if ZERO && cfg!(any(feature = "zeroize", feature = "mlock")) {
+        move_vec::<ZERO>(v, v.len());
+    } else {
+        v.shrink_to_fit();
+    }
+}
+
+#[cfg(feature = "alloc")]
+fn move_vec<const ZERO: bool>(v: &mut Vec<u8>, capacity: usize) {
+    let mut moved = Vec::with_capacity(capacity);
+    lock_vec::<ZERO>(&moved);
+    moved.extend_from_slice(v);
+    release_vec::<ZERO>(v);
+    *v = moved;
+}
+
+// Heap temporary that is locked while alive and released on every exit path.
+#[cfg(feature = "alloc")]
+struct HeapGuard<const ZERO: bool>(Vec<u8>);
+
+#[cfg(feature = "alloc")]
+impl<const ZERO: bool> HeapGuard<ZERO> {
+    fn with_capacity(capacity: usize) -> Self {
+        let v = Vec::with_capacity(capacity);
+        lock_vec::<ZERO>(&v);
+        Self(v)
+    }
+}
+
+#[cfg(feature = "alloc")]
+impl<const ZERO: bool> Drop for HeapGuard<ZERO> {
+    fn drop(&mut self) {
+        release_vec::<ZERO>(&mut self.0);
+    }
+}
+
+// In-place heap `mutate`: restores `target` from `snapshot` unless disarmed,
+// so a failed check or a panicking closure leaves the old content behind.
+#[cfg(feature = "alloc")]
+struct HeapRollback<'a, const ZERO: bool> {
+    target: &'a mut Vec<u8>,
+    snapshot: HeapGuard<ZERO>,
+    armed: bool,
+}
+
+#[cfg(feature = "alloc")]
+impl<const ZERO: bool> Drop for HeapRollback<'_, ZERO> {
+    fn drop(&mut self) {
+        if !self.armed { return; }
+        let old_len = self.snapshot.0.len();
+        self.target[..old_len].copy_from_slice(&self.snapshot.0);
+        wipe::<ZERO>(&mut self.target[old_len..]);
+        self.target.truncate(old_len);
+    }
+}
+
+// Cuts a heap buffer back to `len` on every exit path, wiping the rest.
+#[cfg(feature = "alloc")]
+struct TruncateGuard<'a, const ZERO: bool>(&'a mut Vec<u8>, usize);
+
+#[cfg(feature = "alloc")]
+impl<const ZERO: bool> Drop for TruncateGuard<'_, ZERO> {
+    fn drop(&mut self) {
+        wipe::<ZERO>(&mut self.0[self.1..]);
+        self.0.truncate(self.1);
+    }
+}
+
+// Writes the inline length header for `len` on every exit path. A header
+// kept in the buffer tail is lost once `init_tail` hands that tail out.
+struct HeaderGuard<'a, const N: usize>(&'a mut [u8; N], &'a mut InlineLen, usize);
+
+impl<const N: usize> Drop for HeaderGuard<'_, N> {
+    fn drop(&mut self) {
+        let buf = unsafe { &mut *(self.0 as *mut [u8; N]).cast::<[MaybeUninit<u8>; N]>() };
+        *self.1 = InlineLen::new(self.2, buf);
+    }
+}
+
+// In-place append: cuts the storage back to `len`, wiping the tail, unless disarmed.
+struct AppendRollback<'a, const N: usize, const ZERO: bool> {
+    storage: &'a mut Storage<N>,
+    len: usize,
+    armed: bool,
+}
+
+impl<const N: usize, const ZERO: bool> Drop for AppendRollback<'_, N, ZERO> {
+    fn drop(&mut self) {
+        if self.armed {
+            let end = self.storage.as_bytes().len();
+            let _ = self.storage.splice::<ZERO>(self.len..end, &[]);
+        }
+    }
+}
+
+// In-place stack `mutate`: snapshots only the first `len` bytes and copies
+// them back, with the old header, unless disarmed. Bytes past `len` are never read.
+struct StackRollback<'a, const N: usize, const ZERO: bool> {
+    target: HeaderGuard<'a, N>,
+    snapshot: MaybeUninit<[u8; N]>,
+    len: usize,
+    armed: bool,
+}
+
+impl<'a, const N: usize, const ZERO: bool> StackRollback<'a, N, ZERO> {
+    fn new(target: HeaderGuard<'a, N>) -> Self {
+        let len = target.2;
+        let mut snapshot = MaybeUninit::<[u8; N]>::uninit();
+        unsafe { core::ptr::copy_nonoverlapping(target.0.as_ptr(), snapshot.as_mut_ptr().cast::<u8>(), len) };
+        Self { target, snapshot, len, armed: true }
+    }
+
+    fn snapshot(&mut self) -> &mut [u8] {
+        unsafe { core::slice::from_raw_parts_mut(self.snapshot.as_mut_ptr().cast::<u8>(), self.len) }
+    }
+}
+
+impl<const N: usize, const ZERO: bool> Drop for StackRollback<'_, N, ZERO> {
+    fn drop(&mut self) {
+        let len = self.len;
+        if self.armed {
+            let snapshot = unsafe { core::slice::from_raw_parts(self.snapshot.as_ptr().cast::<u8>(), len) };
+            self.target.0[..len].copy_from_slice(snapshot);
+            self.target.2 = len;
+        }
+        wipe::<ZERO>(self.snapshot());
+    }
+}
+
+// Length errors take precedence over format errors.
+fn validate_bounds<const MIN: usize, const MAX: usize, L: LengthPolicy, F: FormatPolicy>(s: &str) -> Result<usize, BoundedStrError> {
+    let (actual, violation) = match F::validate::<L>(s) {
+        Ok(n) => (n, None),
+        Err(v) => (v.logical_len, Some(v.byte_offset)),
+    };
+    if actual < MIN { return Err(BoundedStrError::TooShort { actual, min: MIN }); }
+    if actual > MAX { return Err(BoundedStrError::TooLong { actual, max: MAX }); }
+    if let Some(byte_offset) = violation { return Err(BoundedStrError::invalid_content_at(s, byte_offset)); }
+    Ok(actual)
+}
+
+// UTF-8 validation and char counting, vectorized with the `simd` feature.
+// ASCII checks stay on `str::is_ascii`, which core already runs word at a time.
+#[inline(always)]
+fn utf8_str(bytes: &[u8]) -> Option<&str> {
+    #[cfg(feature = "simd")]
+    return simdutf8::basic::from_utf8(bytes).ok();
+    #[cfg(not(feature = "simd"))]
+    return str::from_utf8(bytes).ok();
+}
+
+#[cfg(any(feature = "serde", feature = "borsh"))]
+fn utf8_string(bytes: Vec<u8>) -> Result<String, Vec<u8>> {
+    match utf8_str(&bytes) {
+        Some(_) => Ok(unsafe { String::from_utf8_unchecked(bytes) }),
+        None => Err(bytes),
+    }
+}
+
+#[inline(always)]
+fn count_chars(s: &str) -> usize {
+    #[cfg(feature = "simd")]
+    return bytecount::num_chars(s.as_bytes());
+    #[cfg(not(feature = "simd"))]
+    return s.chars().count();
+}
+
+const fn const_decode_char(bytes: &[u8], i: usize) -> Option<char> {
+    let b = bytes[i];
+    let (width, init) = match b {
+        0x00..=0x7F => return Some(b as char),
+        0xC0..=0xDF => (2, (b & 0x1F) as u32),
+        0xE0..=0xEF => (3, (b & 0x0F) as u32),
+        0xF0..=0xF7 => (4, (b & 0x07) as u32),
+        _ => return None,
+    };
+    if i + width > bytes.len() { return None; }
+    let mut code = init;
+    let mut k = 1;
+    while k < width {
+        code = (code << 6) | (bytes[i + k] & 0x3F) as u32;
+        k += 1;
+    }
+    char::from_u32(code)
+}
+
+struct FnWriter<W: FnMut(&str) -> fmt::Result>(W);
+
+impl<W: FnMut(&str) -> fmt::Result> fmt::Write for FnWriter<W> {
+    fn write_str(&mut self, s: &str) -> fmt::Result { (self.0)(s) }
+}
+
+// `masked()` output: a fixed `****` (hiding the length) followed by the kept tail.
+struct Masked<'a>(&'a str);
+
+impl Display for Masked<'_> {
+    fn fmt(&self, f: &mut Formatter<'_>) -> fmt::Result {
+        f.write_str("****")?;
+        f.write_str(self.0)
+    }
+}
+
+impl<const MAX_BYTES: usize> Clone for Storage<MAX_BYTES> {
+    fn clone(&self) -> Self {
+        match self {
+            Self::Stack { .. } => Self::stack(self.as_bytes()),
+            #[cfg(feature = "alloc")]
+            Self::Heap(v) => Self::Heap(v.clone()),
+            #[cfg(feature = "bytes")]
+            Self::Shared(b) => Self::Shared(b.clone()),
+        }
+    }
+}
+
+pub struct BoundedStr<
+    const MIN: usize,
+    const MAX: usize,
+    const MAX_BYTES: usize,
+    L: LengthPolicy = Bytes,
+    F: FormatPolicy = AllowAll,
+	const Z: bool = false,
+    C: EqPolicy = FastEq,
+> {
+    // Heap values also cache `L::logical_len` of the content, kept in sync by
+    // every write path; inline values measure it on demand.
+    storage: Storage<MAX_BYTES>,
+    _marker: PhantomData<(L, F, C, core::convert::Infallible)>, 
+}
+
+// Policies are never instantiated, only named in `PhantomData`, so a marker
+// that isn't `Send` or `Sync` must not take those away. What is stored is
+// plain bytes: an inline buffer, a `Vec<u8>` or a `Bytes` handle.
+unsafe impl<const MIN: usize, const MAX: usize, const MAX_BYTES: usize, L: LengthPolicy, F: FormatPolicy, const Z: bool, C: EqPolicy> Send for BoundedStr<MIN, MAX, MAX_BYTES, L, F, Z, C> {}
+unsafe impl<const MIN: usize, const MAX: usize, const MAX_BYTES: usize, L: LengthPolicy, F: FormatPolicy, const Z: bool, C: EqPolicy> Sync for BoundedStr<MIN, MAX, MAX_BYTES, L, F, Z, C> {}
+impl<const MIN: usize, const MAX: usize, const MAX_BYTES: usize, L: LengthPolicy, F: FormatPolicy, const Z: bool, C: EqPolicy> UnwindSafe for BoundedStr<MIN, MAX, MAX_BYTES, L, F, Z, C> {}
+impl<const MIN: usize, const MAX: usize, const MAX_BYTES: usize, L: LengthPolicy, F: FormatPolicy, const Z: bool, C: EqPolicy> RefUnwindSafe for BoundedStr<MIN, MAX, MAX_BYTES, L, F, Z, C> {}
+
+impl<const MIN: usize, const MAX: usize, const MAX_BYTES: usize, L: LengthPolicy, F: FormatPolicy, const Z: bool, C: EqPolicy>
+    BoundedStr<MIN, MAX, MAX_BYTES, L, F, Z, C>
+{
+    const _CHECK: () = {
+        assert!(MIN <= MAX, "MIN must be <= MAX");
+        #[cfg(not(feature = "alloc"))]
+        {
+            if let ConstLength::Bytes = L::CONST_LENGTH {
+                assert!(MAX_BYTES >= MIN, "MAX_BYTES must be >= MIN for Bytes");
+            }
+            #[cfg(feature = "strict-capacity")]
+            if let ConstLength::Chars = L::CONST_LENGTH {
+                assert!(MAX_BYTES >= MAX.saturating_mul(4), "MAX_BYTES must be >= 4 * MAX for Chars");
+            }
+            #[cfg(feature = "strict-capacity")]
+            if let ConstLength::Utf16 = L::CONST_LENGTH {
+                assert!(MAX_BYTES >= MAX.saturating_mul(3), "MAX_BYTES must be >= 3 * MAX for Utf16Units");
+            }
+        }
+    };
+
+    pub const MIN_LEN: usize = MIN;
+    pub const MAX_LEN: usize = MAX;
+    pub const MAX_INLINE_BYTES: usize = MAX_BYTES;
+
+    const _EMPTY_ALLOWED: () = {
+        assert!(MIN == 0, "Default requires MIN == 0");
+        assert!(F::ACCEPTS_EMPTY, "Default requires a format policy with ACCEPTS_EMPTY");
+    };
+
+    // Checked by every serializer: serde, borsh and rkyv.
+    #[cfg(any(feature = "serde", feature = "borsh", feature = "rkyv"))]
+    const _SERIALIZE_ALLOWED: () =
+        assert!(!Z || cfg!(feature = "serialize-secrets"), "serializing a Z = true value requires the serialize-secrets feature");
+
+    #[inline(always)]
+	pub fn len_bytes(&self) -> usize {
+        match &self.storage {
+            Storage::Stack { buf, len } => len.get(buf),
+            #[cfg(feature = "alloc")]
+            Storage::Heap(v) => v.len(),
+            #[cfg(feature = "bytes")]
+            Storage::Shared(b) => b.len(),
+        }
+    }
+
+    #[inline(always)]
+    pub fn len_logical(&self) -> usize {
+        match self.storage.cached_logical_len() {
+            Some(n) => n,
+            None if L::CONST_LENGTH == ConstLength::Bytes => self.len_bytes(),
+            None => L::logical_len(self.as_str()),
+        }
+    }
+
+    #[inline(always)]
+    fn from_parts(mut storage: Storage<MAX_BYTES>, logical_len: usize) -> Self {
+        storage.set_logical_len(logical_len);
+        Self { storage, _marker: PhantomData }
+    }
+
+    #[inline(always)]
+    fn set_logical_len(&mut self, n: usize) {
+        self.storage.set_logical_len(n);
+    }
+
+    #[inline(always)]
+    pub fn is_empty(&self) -> bool {
+        self.len_bytes() == 0
+    }
+
+    #[inline(always)]
+    pub fn is_heap(&self) -> bool {
+        self.storage.is_heap()
+    }
+
+    #[inline(always)]
+    pub fn capacity(&self) -> usize {
+        self.storage.capacity()
+    }
+
+    #[inline(always)]
+    pub fn remaining_logical(&self) -> usize {
+        MAX.saturating_sub(self.len_logical())
+    }
+
+    #[inline(always)]
+    pub fn remaining_bytes(&self) -> usize {
+        self.capacity().saturating_sub(self.len_bytes())
+    }
+
+    // Moves a spilled value back into the inline buffer once it fits under
+    // `MAX_BYTES` again, wiping and freeing the heap buffer. Returns whether
+    // the value is inline afterwards.
+    pub fn try_inline(&mut self) -> bool {
+        self.storage.inline::<Z>()
+    }
+
+    // Moves an inline value to the heap ahead of growth that won't fit inline.
+    #[cfg(feature = "alloc")]
+    pub fn spill_to_heap(&mut self) {
+        let logical_len = self.len_logical();
+        self.storage.spill::<Z>(0);
+        self.set_logical_len(logical_len);
+    }
+
+    // Same as `capacity()`; named to match `len_bytes()`.
+    #[inline(always)]
+    pub fn capacity_bytes(&self) -> usize {
+        self.storage.capacity()
+    }
+
+    // Makes room for at least `additional` more bytes, spilling to the heap if
+    // they won't fit inline. Allocates exactly, unlike `Vec::reserve`.
+    #[cfg(feature = "alloc")]
+    pub fn reserve(&mut self, additional: usize) {
+        let logical_len = self.len_logical();
+        self.storage.reserve::<Z>(additional);
+        self.set_logical_len(logical_len);
+    }
+
+    // Drops spare heap capacity. Inline values are left as they are; see `try_inline()`.
+    #[cfg(feature = "alloc")]
+    pub fn shrink_to_fit(&mut self) {
+        if let Storage::Heap(v) = &mut self.storage {
+            shrink_vec::<Z>(v);
+        }
+    }
+
+    // Returns the logical length, so callers can cache it.
+    fn validate(s: &str) -> Result<usize, BoundedStrError> {
+        let () = Self::_CHECK;
+        validate_bounds::<MIN, MAX, L, F>(s)
+    }
+
+    const fn const_logical_len(bytes: &[u8]) -> usize {
+        match L::CONST_LENGTH {
+            ConstLength::Bytes => bytes.len(),
+            ConstLength::Chars => {
+                let (mut i, mut n) = (0, 0);
+                while i < bytes.len() {
+                    if bytes[i] & 0xC0 != 0x80 { n += 1; }
+                    i += 1;
+                }
+                n
+            }
+            ConstLength::Utf16 => {
+                let (mut i, mut n) = (0, 0);
+                while i < bytes.len() {
+                    if bytes[i] & 0xC0 != 0x80 { n += if bytes[i] >= 0xF0 { 2 } else { 1 }; }
+                    i += 1;
+                }
+                n
+            }
+            ConstLength::Unsupported => panic!("length policy cannot be evaluated at compile time"),
+        }
+    }
+
+    pub const fn check_const(s: &str) -> Result<(), BoundedStrError> {
+        let () = Self::_CHECK;
+        let bytes = s.as_bytes();
+        let logical_len = Self::const_logical_len(bytes);
+        if logical_len < MIN { return Err(BoundedStrError::TooShort { actual: logical_len, min: MIN }); }
+        if logical_len > MAX { return Err(BoundedStrError::TooLong { actual: logical_len, max: MAX }); }
+        #[cfg(not(feature = "alloc"))]
+        if bytes.len() > MAX_BYTES { return Err(BoundedStrError::TooManyBytes { actual: bytes.len(), max_bytes: MAX_BYTES }); }
+
+        match F::CONST_FORMAT {
+            ConstFormat::AllowAll => Ok(()),
+            ConstFormat::AsciiOnly => {
+                let mut i = 0;
+                while i < bytes.len() {
+                    if !bytes[i].is_ascii() {
+                        return Err(BoundedStrError::InvalidContent { byte_offset: i, char: const_decode_char(bytes, i) });
+                    }
+                    i += 1;
+                }
+                Ok(())
+            }
+            ConstFormat::Charset(mask) => {
+                let mut i = 0;
+                while i < bytes.len() {
+                    if !in_charset(mask, bytes[i]) {
+                        return Err(BoundedStrError::InvalidContent { byte_offset: i, char: const_decode_char(bytes, i) });
+                    }
+                    i += 1;
+                }
+                Ok(())
+            }
+            ConstFormat::Unsupported => panic!("format policy cannot be evaluated at compile time"),
+        }
+    }
+
+    #[doc(hidden)]
+    pub const fn __assert_literal(s: &str) {
+        if let Err(e) = Self::check_const(s) {
+            panic!("{}", e.message());
+        }
+    }
+
+    pub const fn new_const(s: &str) -> Self {
+        Self::__assert_literal(s);
+        let bytes = s.as_bytes();
+        if bytes.len() > MAX_BYTES {
+            panic!("too many bytes for buffer");
+        }
+
+        let mut buf = Storage::<MAX_BYTES>::UNINIT;
+        let mut i = 0;
+        while i < bytes.len() {
+            buf[i] = MaybeUninit::new(bytes[i]);
+            i += 1;
+        }
+        let len = InlineLen::new(bytes.len(), &mut buf);
+        Self { storage: Storage::Stack { buf, len }, _marker: PhantomData }
+    }
+
+    pub fn new(s: &str) -> Result<Self, BoundedStrError> {
+        let logical_len = Self::validate(s)?;
+
+        let byte_len = s.len();
+
+        #[cfg(feature = "alloc")]
+        if byte_len > MAX_BYTES {
+            let storage = Storage::heap(s.as_bytes().to_vec());
+            storage.lock::<Z>();
+            return Ok(Self::from_parts(storage, logical_len));
+        }
+
+        if byte_len > MAX_BYTES {
+            return Err(BoundedStrError::TooManyBytes { actual: byte_len, max_bytes: MAX_BYTES });
+        }
+
+        Ok(Self::from_parts(Storage::stack(s.as_bytes()), logical_len))
+    }
+
+    pub fn new_trimmed(s: &str) -> Result<Self, BoundedStrError> {
+        Self::new(s.trim())
+    }
+
+    pub fn new_truncate(s: &str) -> Result<Self, BoundedStrError> {
+        Self::new(&s[..Self::fit_offset(s)])
+    }
+
+    // Pads `s` with `fill` up to `MIN` units, for fixed-width fields such as
+    // `BoundedStr<N, N>`. A fill char wider than one unit can overshoot `MIN`,
+    // and with it `MAX`; the result is checked like `new()`.
+    pub fn new_padded(s: &str, fill: char, side: PadSide) -> Result<Self, BoundedStrError> {
+        let mut fill_buf = [0u8; 4];
+        let fill: &str = fill.encode_utf8(&mut fill_buf);
+        let count = match L::logical_len(fill) {
+            0 => 0,
+            width => MIN.saturating_sub(L::logical_len(s)).div_ceil(width),
+        };
+        let (left, right) = match side {
+            PadSide::Left => (count, 0),
+            PadSide::Right => (0, count),
+            PadSide::Center => (count / 2, count - count / 2),
+        };
+        let mut work = Storage::empty();
+        #[cfg(feature = "alloc")]
+        work.reserve::<Z>(s.len().saturating_add(count.saturating_mul(fill.len())));
+        let res = (0..left).try_for_each(|_| work.push::<Z>(fill.as_bytes()))
+            .and_then(|()| work.push::<Z>(s.as_bytes()))
+            .and_then(|()| (0..right).try_for_each(|_| work.push::<Z>(fill.as_bytes())));
+        if let Err(e) = res {
+            work.wipe::<Z>();
+            return Err(e);
+        }
+        Self::from_storage(work)
+    }
+
+    // `new_padded()` into a type with a larger `MIN`, e.g. a fixed-width record field.
+    pub fn pad_to_min<const MIN2: usize, const MAX2: usize, const MAX_BYTES2: usize>(
+        &self,
+        fill: char,
+        side: PadSide,
+    ) -> Result<BoundedStr<MIN2, MAX2, MAX_BYTES2, L, F, Z, C>, BoundedStrError> {
+        BoundedStr::new_padded(self.as_str(), fill, side)
+    }
+
+    // Shortens to `max_logical` units including `ellipsis`, cutting where
+    // `L::byte_offset` puts the boundary, e.g. "Hello, world" -> "Hello, w…".
+    // Values that already fit are returned as they are. The ellipsis is never
+    // cut, so one longer than `max_logical` replaces the whole content.
+    pub fn truncate_with_ellipsis(&self, max_logical: usize, ellipsis: &str) -> Result<Self, BoundedStrError> {
+        if self.len_logical() <= max_logical { return Ok(self.clone()); }
+        let s = self.as_str();
+        #[allow(unused_mut)]
+        let mut end = L::byte_offset(s, max_logical.saturating_sub(L::logical_len(ellipsis)));
+        #[cfg(not(feature = "alloc"))]
+        {
+            end = Bytes::byte_offset(&s[..end], MAX_BYTES.saturating_sub(ellipsis.len()));
+        }
+        let mut work = Storage::empty();
+        if let Err(e) = work.push::<Z>(&s.as_bytes()[..end]).and_then(|()| work.push::<Z>(ellipsis.as_bytes())) {
+            work.wipe::<Z>();
+            return Err(e);
+        }
+        Self::from_storage(work)
+    }
+
+    // End of the longest prefix of `s` that fits `MAX` (and `MAX_BYTES` without `alloc`).
+    fn fit_offset(s: &str) -> usize {
+        #[allow(unused_mut)]
+        let mut end = L::byte_offset(s, MAX);
+        #[cfg(not(feature = "alloc"))]
+        {
+            end = Bytes::byte_offset(&s[..end], MAX_BYTES);
+        }
+        end
+    }
+
+    // Splits `input` on `sep` and checks every field like `new()`. Errors don't
+    // end the iteration, so `collect::<Result<Vec<_>, _>>()` stops at the first.
+    pub fn split_bounded<'a>(input: &'a str, sep: &'a str) -> impl Iterator<Item = Result<Self, BoundedStrError>> {
+        input.split(sep).map(Self::new)
+    }
+
+    // Like `split_bounded()`, over `str::lines()`.
+    pub fn lines_bounded(input: &str) -> impl Iterator<Item = Result<Self, BoundedStrError>> {
+        input.lines().map(Self::new)
+    }
+
+    // Splits `input` into pieces of at most `MAX` units, cut where `L::byte_offset`
+    // puts the boundary (grapheme boundaries for `Graphemes`). Every piece is
+    // checked like `new()`, so a short last piece can fail `MIN`; iteration
+    // stops after the first error. Empty input yields nothing.
+    pub fn chunks_of(input: &str) -> impl Iterator<Item = Result<Self, BoundedStrError>> + '_ {
+        let mut rest = input;
+        core::iter::from_fn(move || {
+            if rest.is_empty() { return None; }
+            // A unit wider than `MAX` on its own is handed to `new()` to report.
+            let end = match Self::fit_offset(rest) {
+                0 => rest.chars().next().map_or(0, char::len_utf8),
+                end => end,
+            };
+            let (piece, tail) = rest.split_at(end);
+            let res = Self::new(piece);
+            rest = if res.is_ok() { tail } else { "" };
+            Some(res)
+        })
+    }
+
+    pub fn from_utf8(bytes: &[u8]) -> Result<Self, BoundedStrError> {
+        let s = utf8_str(bytes).ok_or(BoundedStrError::InvalidUtf8)?;
+        Self::new(s)
+    }
+
+    pub fn from_utf8_lossy(bytes: &[u8]) -> Result<Self, BoundedStrError> {
+        if let Some(s) = utf8_str(bytes) {
+            return Self::new(s);
+        }
+        Self::try_from_chars(bytes.utf8_chunks().flat_map(|chunk| {
+            let replacement = (!chunk.invalid().is_empty()).then_some(char::REPLACEMENT_CHARACTER);
+            chunk.valid().chars().chain(replacement)
+        }))
+    }
+
+    // Most bytes a streamed value (`from_reader()`, `BoundedStrBuilder`) may take.
+    // `Graphemes` and `DisplayWidth` units have no byte bound, so they get room
+    // for four bytes per unit or `MAX_BYTES`, whichever is larger. Longer values
+    // that `new()` accepts, e.g. clusters stacking many combining marks, fail
+    // with `TooManyBytes`; raise `MAX_BYTES` to admit them.
+    const BYTE_LIMIT: usize = match L::CONST_LENGTH {
+        ConstLength::Bytes => MAX,
+        ConstLength::Chars => MAX.saturating_mul(4),
+        ConstLength::Utf16 => MAX.saturating_mul(3),
+        ConstLength::Unsupported => {
+            let per_char = MAX.saturating_mul(4);
+            if per_char > MAX_BYTES { per_char } else { MAX_BYTES }
+        }
+    };
+
+    // Reads until EOF but never buffers more than `BYTE_LIMIT + 1` bytes, and
+    // checks UTF-8 as chunks arrive, so an untrusted stream fails as soon as it
+    // is too long or malformed. Validation errors come back as `InvalidData`
+    // wrapping the `BoundedStrError`; `TooManyBytes` then reports the limit + 1.
+    #[cfg(feature = "std")]
+    pub fn from_reader<R: std::io::Read>(mut r: R) -> std::io::Result<Self> {
+        let invalid = |e: BoundedStrError| std::io::Error::new(std::io::ErrorKind::InvalidData, e);
+        let mut builder = BoundedStrBuilder::<MIN, MAX, MAX_BYTES, L, F, Z, C>::new();
+        let mut chunk = [0u8; 8 * 1024];
+        let res = loop {
+            // Never asks for more than one byte past the limit.
+            let want = core::cmp::min(chunk.len(), Self::BYTE_LIMIT.saturating_add(1) - builder.len_bytes());
+            match r.read(&mut chunk[..want]) {
+                Ok(0) => break builder.finish().map_err(invalid),
+                Ok(n) => if let Err(e) = builder.push_chunk(&chunk[..n]) { break Err(invalid(e)) },
+                Err(e) if e.kind() == std::io::ErrorKind::Interrupted => {}
+                Err(e) => break Err(e),
+            }
+        };
+        wipe::<Z>(&mut chunk);
+        res
+    }
+
+    // Async `from_reader()` for any `futures-io` reader (async-std, smol, or
+    // tokio through `tokio-util`'s compat layer).
+    #[cfg(feature = "futures-io")]
+    pub async fn from_async_reader<R: futures_io::AsyncRead + Unpin>(mut r: R) -> std::io::Result<Self> {
+        let invalid = |e: BoundedStrError| std::io::Error::new(std::io::ErrorKind::InvalidData, e);
+        let mut builder = BoundedStrBuilder::<MIN, MAX, MAX_BYTES, L, F, Z, C>::new();
+        // Smaller than in `from_reader()`: it lives in the future's state.
+        let mut chunk = [0u8; 1024];
+        let res = loop {
+            let want = core::cmp::min(chunk.len(), Self::BYTE_LIMIT.saturating_add(1) - builder.len_bytes());
+            let buf = &mut chunk[..want];
+            match core::future::poll_fn(|cx| core::pin::Pin::new(&mut r).poll_read(cx, buf)).await {
+                Ok(0) => break builder.finish().map_err(invalid),
+                Ok(n) => if let Err(e) = builder.push_chunk(&chunk[..n]) { break Err(invalid(e)) },
+                Err(e) if e.kind() == std::io::ErrorKind::Interrupted => {}
+                Err(e) => break Err(e),
+            }
+        };
+        wipe::<Z>(&mut chunk);
+        res
+    }
+
+    pub fn from_utf16(units: &[u16]) -> Result<Self, BoundedStrError> {
+        if char::decode_utf16(units.iter().copied()).any(|c| c.is_err()) {
+            return Err(BoundedStrError::InvalidUtf16);
+        }
+        Self::from_utf16_lossy(units)
+    }
+
+    pub fn from_utf16_lossy(units: &[u16]) -> Result<Self, BoundedStrError> {
+        Self::try_from_chars(
+            char::decode_utf16(units.iter().copied())
+                .map(|c| c.unwrap_or(char::REPLACEMENT_CHARACTER)),
+        )
+    }
+
+    fn from_storage(mut storage: Storage<MAX_BYTES>) -> Result<Self, BoundedStrError> {
+        match Self::validate(unsafe { str::from_utf8_unchecked(storage.as_bytes()) }) {
+            Ok(logical_len) => Ok(Self::from_parts(storage, logical_len)),
+            Err(e) => {
+                storage.wipe::<Z>();
+                Err(e)
+            }
+        }
+    }
+
+    // Re-measures at doubling byte checkpoints so unbounded input stops early in O(n).
+    fn push_checked(work: &mut Storage<MAX_BYTES>, bytes: &[u8], checkpoint: &mut usize) -> Result<(), BoundedStrError> {
+        work.push::<Z>(bytes)?;
+        let len = work.as_bytes().len();
+        if len > *checkpoint {
+            let actual = L::logical_len(unsafe { str::from_utf8_unchecked(work.as_bytes()) });
+            if actual > MAX {
+                return Err(BoundedStrError::TooLong { actual, max: MAX });
+            }
+            *checkpoint = len.saturating_mul(2);
+        }
+        Ok(())
+    }
+
+    fn extend_storage<I: IntoIterator<Item = char>>(work: &mut Storage<MAX_BYTES>, chars: I) -> Result<(), BoundedStrError> {
+        let mut checkpoint = MAX;
+        for c in chars {
+            Self::push_checked(work, c.encode_utf8(&mut [0u8; 4]).as_bytes(), &mut checkpoint)?;
+        }
+        Ok(())
+    }
+
+    pub fn try_from_chars<I: IntoIterator<Item = char>>(chars: I) -> Result<Self, BoundedStrError> {
+        let mut work = Storage::empty();
+        if let Err(e) = Self::extend_storage(&mut work, chars) {
+            work.wipe::<Z>();
+            return Err(e);
+        }
+        Self::from_storage(work)
+    }
+
+    pub fn try_extend<I: IntoIterator<Item = char>>(&mut self, chars: I) -> Result<(), BoundedStrError> {
+        let mut editor = self.edit();
+        Self::extend_storage(&mut editor.work, chars)?;
+        editor.commit()
+    }
+
+    pub fn try_from_fmt(args: fmt::Arguments<'_>) -> Result<Self, BoundedStrError> {
+        if let Some(s) = args.as_str() {
+            return Self::new(s);
+        }
+
+        let mut work = Storage::empty();
+        let mut checkpoint = MAX;
+        let mut error = None;
+        let written = fmt::write(&mut FnWriter(|s: &str| {
+            Self::push_checked(&mut work, s.as_bytes(), &mut checkpoint).map_err(|e| {
+                error = Some(e);
+                fmt::Error
+            })
+        }), args);
+
+        if written.is_err() {
+            let byte_offset = work.as_bytes().len();
+            work.wipe::<Z>();
+            return Err(error.unwrap_or(BoundedStrError::InvalidContent { byte_offset, char: None }));
+        }
+        Self::from_storage(work)
+    }
+
+    // Matches `str::to_lowercase`, including word-final `Σ` -> `ς`. Text with a
+    // `Σ` is lowercased through a temporary `String` (wiped when `Z = true`);
+    // without `alloc` the word-final rule is approximated with `char` case checks.
+    pub fn try_to_lowercase(&self) -> Result<Self, BoundedStrError> {
+        let s = self.as_str();
+        if !s.contains('Σ') {
+            return Self::try_from_chars(s.chars().flat_map(char::to_lowercase));
+        }
+        #[cfg(feature = "alloc")]
+        {
+            let mut lower = s.to_lowercase().into_bytes();
+            let res = Self::new(unsafe { str::from_utf8_unchecked(&lower) });
+            wipe_vec::<Z>(&mut lower);
+            res
+        }
+        #[cfg(not(feature = "alloc"))]
+        Self::try_from_chars(s.char_indices().flat_map(|(i, c)| {
+            let word_final = c == 'Σ' && is_word_final_sigma(s, i);
+            c.to_lowercase().map(move |l| if word_final { 'ς' } else { l })
+        }))
+    }
+
+    pub fn try_to_uppercase(&self) -> Result<Self, BoundedStrError> {
+        Self::try_from_chars(self.chars().flat_map(char::to_uppercase))
+    }
+
+    #[cfg(feature = "std")]
+    pub fn writer(&mut self) -> BoundedWriter<'_, MIN, MAX, MAX_BYTES, L, F, Z, C> {
+        BoundedWriter { target: self, pending: [0; 4], pending_len: 0 }
+    }
+
+    pub fn edit(&mut self) -> BoundedStrEditor<'_, MIN, MAX, MAX_BYTES, L, F, Z, C> {
+        let work = self.storage.clone();
+        work.lock::<Z>();
+        let checkpoint = core::cmp::max(MAX, work.as_bytes().len());
+        BoundedStrEditor { target: self, work, checkpoint }
+    }
+
+    fn try_splice(&mut self, range: Range<usize>, with: &str) -> Result<(), BoundedStrError> {
+        let mut editor = self.edit();
+        editor.replace_range(range, with)?;
+        editor.commit()
+    }
+
+    pub fn try_push_str(&mut self, s: &str) -> Result<(), BoundedStrError> {
+        let end = self.len_bytes();
+        if s.len() > self.remaining_bytes() {
+            return self.try_splice(end..end, s);
+        }
+        // Fits the current capacity: append in place and cut back on failure.
+        let mut rollback = AppendRollback::<MAX_BYTES, Z> { storage: &mut self.storage, len: end, armed: true };
+        let _ = rollback.storage.push::<Z>(s.as_bytes());
+        let logical_len = Self::validate(unsafe { str::from_utf8_unchecked(rollback.storage.as_bytes()) })?;
+        rollback.armed = false;
+        drop(rollback);
+        self.set_logical_len(logical_len);
+        Ok(())
+    }
+
+    pub fn try_push(&mut self, c: char) -> Result<(), BoundedStrError> {
+        self.try_push_str(c.encode_utf8(&mut [0u8; 4]))
+    }
+
+    pub fn try_concat(&self, other: &str) -> Result<Self, BoundedStrError> {
+        let mut out = self.clone();
+        out.try_push_str(other)?;
+        Ok(out)
+    }
+
+    pub fn try_repeat(&self, n: usize) -> Result<Self, BoundedStrError> {
+        let mut work = Storage::empty();
+        let mut checkpoint = MAX;
+        let times = if self.is_empty() { 0 } else { n };
+        for _ in 0..times {
+            if let Err(e) = Self::push_checked(&mut work, self.as_bytes(), &mut checkpoint) {
+                work.wipe::<Z>();
+                return Err(e);
+            }
+        }
+        Self::from_storage(work)
+    }
+
+    pub fn try_insert_str(&mut self, at: usize, s: &str) -> Result<(), BoundedStrError> {
+        self.try_splice(at..at, s)
+    }
+
+    pub fn try_remove_range(&mut self, range: Range<usize>) -> Result<(), BoundedStrError> {
+        self.try_splice(range, "")
+    }
+
+    // Like `String::split_off`: `self` keeps `..at` and the rest is returned.
+    // Both halves are checked like `new()`; on failure `self` is unchanged.
+    pub fn try_split_off(&mut self, at: usize) -> Result<Self, BoundedStrError> {
+        let tail = Self::new(self.get(at..).ok_or(BoundedStrError::NotCharBoundary)?)?;
+        self.try_remove_range(at..self.len_bytes())?;
+        Ok(tail)
+    }
+
+    // Removes `range` and returns it as a value of another size, e.g. a frame
+    // header cut off a buffer. Both parts are checked; on failure `self` is unchanged.
+    pub fn drain<const MIN2: usize, const MAX2: usize, const MAX_BYTES2: usize>(
+        &mut self,
+        range: Range<usize>,
+    ) -> Result<BoundedStr<MIN2, MAX2, MAX_BYTES2, L, F, Z, C>, BoundedStrError> {
+        let drained = BoundedStr::new(self.get(range.clone()).ok_or(BoundedStrError::NotCharBoundary)?)?;
+        self.try_remove_range(range)?;
+        Ok(drained)
+    }
+
+    pub fn retain<P: FnMut(char) -> bool>(&mut self, keep: P) -> Result<(), BoundedStrError> {
+        let mut editor = self.edit();
+        editor.retain(keep);
+        editor.commit()
+    }
+
+    pub fn try_replace(&self, from: &str, to: &str) -> Result<Self, BoundedStrError> {
+        let mut out = self.clone();
+        let mut editor = out.edit();
+        editor.truncate(0)?;
+        let mut last = 0;
+        for (i, m) in self.as_str().match_indices(from) {
+            editor.push_str(&self[last..i])?.push_str(to)?;
+            last = i + m.len();
+        }
+        editor.push_str(&self[last..])?;
+        editor.commit()?;
+        Ok(out)
+    }
+
+    // `Ok(None)` when empty; an error when removing the last char would break
+    // `MIN` or the format policy, leaving the value unchanged.
+    pub fn try_pop(&mut self) -> Result<Option<char>, BoundedStrError> {
+        let Some(c) = self.as_str().chars().next_back() else { return Ok(None) };
+        let end = self.len_bytes();
+        self.try_splice(end - c.len_utf8()..end, "")?;
+        Ok(Some(c))
+    }
+
+    // Like `try_pop()`, but a rejected pop also returns `None`.
+    pub fn pop(&mut self) -> Option<char> {
+        self.try_pop().ok().flatten()
+    }
+
+    pub fn truncate_logical(&mut self, n: usize) -> Result<(), BoundedStrError> {
+        let end = self.len_bytes();
+        let at = L::byte_offset(self.as_str(), n);
+        if at == end { return Ok(()); }
+        self.try_splice(at..end, "")
+    }
+
+    pub fn clear(&mut self) -> Result<(), BoundedStrError> {
+        if MIN > 0 { return Err(BoundedStrError::TooShort { actual: 0, min: MIN }); }
+        let end = self.len_bytes();
+        self.try_splice(0..end, "")
+    }
+
+    // Replaces the content with `s`, validated like `new()`, reusing the current
+    // stack buffer or heap allocation. The old bytes are wiped when `Z` is set.
+    pub fn set(&mut self, s: &str) -> Result<(), BoundedStrError> {
+        let logical_len = Self::validate(s)?;
+        let end = self.len_bytes();
+        self.storage.splice::<Z>(0..end, s.as_bytes())?;
+        self.set_logical_len(logical_len);
+        Ok(())
+    }
+
+    fn check_mutation(bytes: &[u8]) -> Result<usize, BoundedStrError> {
+        let kind = match utf8_str(bytes) {
+            None => MutationErrorKind::InvalidUtf8,
+            Some(s) => match F::validate::<L>(s) {
+                Ok(n) | Err(Violation { logical_len: n, .. }) if n < MIN || n > MAX => MutationErrorKind::LengthViolation,
+                Ok(n) => return Ok(n),
+                Err(_) => MutationErrorKind::PolicyViolation,
+            },
+        };
+        Err(BoundedStrError::MutationFailed { kind })
+    }
+
+    pub fn mutate<Mut, R>(&mut self, mutator: Mut) -> Result<R, BoundedStrError>
+    where
+        Mut: FnOnce(&mut [u8], &mut usize) -> R, 
+    {
+        self.storage.make_mut::<Z>();
+        match &mut self.storage {
+            Storage::Stack { buf, len } => {
+                let old_len = len.get(buf);
+                let mut rollback = StackRollback::<MAX_BYTES, Z>::new(HeaderGuard(init_tail(buf, old_len), len, old_len));
+                let mut temp_len = old_len;
+                let res = mutator(rollback.target.0, &mut temp_len);
+				
+                if temp_len > MAX_BYTES {
+                    return Err(BoundedStrError::TooManyBytes { actual: temp_len, max_bytes: MAX_BYTES });
+                }
+
+                Self::check_mutation(&rollback.target.0[..temp_len])?;
+                rollback.armed = false;
+                rollback.target.2 = temp_len;
+                Ok(res)
+            }
+
+            #[cfg(feature = "alloc")]            
+            Storage::Heap(h) => {
+                let Spilled { bytes: v, logical_len } = &mut **h;
+                let limit = core::cmp::max(MAX, MAX_BYTES);
+                let old_len = v.len();
+
+                // The closure works on `v` itself; only the rollback copy is made.
+                let mut snapshot = HeapGuard::<Z>::with_capacity(old_len);
+                snapshot.0.extend_from_slice(v);
+                grow_vec::<Z>(v, core::cmp::max(limit, old_len));
+                v.resize(core::cmp::max(limit, old_len), 0);
+                let mut rollback = HeapRollback { target: v, snapshot, armed: true };
+
+                let mut temp_len = old_len;
+                let res = mutator(rollback.target, &mut temp_len);
+
+                if temp_len > limit { 
+                    return Err(BoundedStrError::TooManyBytes { actual: temp_len, max_bytes: limit });
+                }
+
+                *logical_len = Some(Self::check_mutation(&rollback.target[..temp_len])?);
+                wipe::<Z>(&mut rollback.target[temp_len..]);
+                rollback.target.truncate(temp_len);
+                rollback.armed = false;
+                Ok(res)
+            }
+
+            #[cfg(feature = "bytes")]
+            Storage::Shared(_) => unreachable!(),
+        }
+    }
+
+    // `mutate` without the rollback snapshot and without the UTF-8, length and
+    // format checks; only the cached logical length is recomputed.
+    //
+    // Safety: when `mutator` returns, `len` must not exceed the buffer and
+    // `buf[..len]` must be valid UTF-8 of `MIN..=MAX` logical units that `F`
+    // accepts. If `mutator` panics, the old `len` bytes must still be valid.
+    #[allow(clippy::missing_safety_doc)]
+    pub unsafe fn mutate_in_place_unchecked<Mut, R>(&mut self, mutator: Mut) -> R
+    where
+        Mut: FnOnce(&mut [u8], &mut usize) -> R,
+    {
+        self.storage.make_mut::<Z>();
+        let res = match &mut self.storage {
+            Storage::Stack { buf, len } => {
+                let old_len = len.get(buf);
+                let mut guard = HeaderGuard(init_tail(buf, old_len), len, old_len);
+                let mut new_len = old_len;
+                let res = mutator(guard.0, &mut new_len);
+                guard.2 = new_len;
+                res
+            }
+
+            #[cfg(feature = "alloc")]
+            Storage::Heap(v) => {
+                let old_len = v.len();
+                let size = core::cmp::max(core::cmp::max(MAX, MAX_BYTES), old_len);
+                grow_vec::<Z>(v, size);
+                v.resize(size, 0);
+                let mut guard = TruncateGuard::<Z>(v, old_len);
+                let mut new_len = old_len;
+                let res = mutator(guard.0, &mut new_len);
+                guard.1 = new_len;
+                res
+            }
+
+            #[cfg(feature = "bytes")]
+            Storage::Shared(_) => unreachable!(),
+        };
+        debug_assert!(Self::check_mutation(self.as_bytes()).is_ok(), "mutate_in_place_unchecked broke the invariants");
+        self.set_logical_len(L::logical_len(self.as_str()));
+        res
+    }
+
+    // Same as `as_str()`, spelled out at call sites that knowingly read a secret.
+    #[inline(always)]
+    pub fn expose_secret(&self) -> &str { self.as_str() }
+
+    // Shows only the last `keep_last` chars, e.g. `****1234`. If that would
+    // reveal the whole value, nothing but the mask is shown.
+    pub fn masked(&self, keep_last: usize) -> impl Display + '_ {
+        let s = self.as_str();
+        let tail = match keep_last.checked_sub(1).and_then(|n| s.char_indices().rev().nth(n)) {
+            Some((i, _)) if i > 0 => &s[i..],
+            _ => "",
+        };
+        Masked(tail)
+    }
+
+    #[inline(always)]
+	pub fn as_str(&self) -> &str {
+        unsafe { str::from_utf8_unchecked(self.storage.as_bytes()) }
+    }
+	
+	#[inline(always)]
+    pub fn get<I: SliceIndex<str>>(&self, index: I) -> Option<&I::Output> {
+        self.as_str().get(index)
+    }
+
+	#[inline(always)]
+    pub fn as_bytes(&self) -> &[u8] {
+        self.storage.as_bytes()
+    }
+	
+    fn into_storage(self) -> Storage<MAX_BYTES> {
+        let this = ManuallyDrop::new(self);
+        unsafe { core::ptr::read(&this.storage) }
+    }
+
+    fn convert_storage<const MAX_BYTES2: usize>(self) -> Result<Storage<MAX_BYTES2>, BoundedStrError> {
+        match self.into_storage() {
+            #[cfg(feature = "alloc")]
+            Storage::Heap(v) => Ok(Storage::Heap(v)),
+            #[cfg(feature = "bytes")]
+            Storage::Shared(b) => Ok(Storage::Shared(b)),
+            Storage::Stack { mut buf, len } => {
+                let mut out = Storage::empty();
+                let res = out.push::<Z>(unsafe { assume_init(&buf[..len.get(&buf)]) });
+                wipe_uninit::<Z>(&mut buf);
+                res.map(|()| out)
+            }
+        }
+    }
+
+    pub fn widen<const MIN2: usize, const MAX2: usize, const MAX_BYTES2: usize>(self) -> BoundedStr<MIN2, MAX2, MAX_BYTES2, L, F, Z, C> {
+        const {
+            assert!(MIN2 <= MIN && MAX2 >= MAX, "widen requires MIN2 <= MIN and MAX2 >= MAX");
+            #[cfg(not(feature = "alloc"))]
+            assert!(MAX_BYTES2 >= MAX_BYTES, "widen requires MAX_BYTES2 >= MAX_BYTES without alloc");
+        }
+        let logical_len = self.len_logical();
+        match self.convert_storage() {
+            Ok(storage) => BoundedStr::from_parts(storage, logical_len),
+            Err(_) => unreachable!(),
+        }
+    }
+
+    pub fn narrow<const MIN2: usize, const MAX2: usize, const MAX_BYTES2: usize>(self) -> Result<BoundedStr<MIN2, MAX2, MAX_BYTES2, L, F, Z, C>, BoundedStrError> {
+        let logical_len = self.len_logical();
+        let () = BoundedStr::<MIN2, MAX2, MAX_BYTES2, L, F, Z, C>::_CHECK;
+        if logical_len < MIN2 { return Err(BoundedStrError::TooShort { actual: logical_len, min: MIN2 }); }
+        if logical_len > MAX2 { return Err(BoundedStrError::TooLong { actual: logical_len, max: MAX2 }); }
+        Ok(BoundedStr::from_parts(self.convert_storage()?, logical_len))
+    }
+
+    pub fn refine<F2: FormatPolicy>(self) -> Result<BoundedStr<MIN, MAX, MAX_BYTES, L, F2, Z, C>, BoundedStrError> {
+        if !F2::check(self.as_str()) { return Err(BoundedStrError::invalid_content::<F2>(self.as_str())); }
+        Ok(BoundedStr { storage: self.into_storage(), _marker: PhantomData })
+    }
+
+    pub fn relax<F2: FormatPolicy>(self) -> BoundedStr<MIN, MAX, MAX_BYTES, L, F2, Z, C>
+    where
+        F: Refines<F2>,
+    {
+        BoundedStr { storage: self.into_storage(), _marker: PhantomData }
+    }
+
+    #[cfg(feature = "alloc")]
+    pub fn into_bytes(mut self) -> Vec<u8> {
+        // A page-locked buffer is copied out and released with `self` instead.
+        if let (false, Storage::Heap(v)) = (Z && cfg!(feature = "mlock"), &mut self.storage) {
+            return core::mem::take(v);
+        }
+        #[cfg(feature = "bytes")]
+        if let Storage::Shared(b) = &mut self.storage {
+            return Vec::from(core::mem::take(&mut b.bytes));
+        }
+        self.as_bytes().to_vec()
+    }
+
+    #[cfg(feature = "alloc")]
+    pub fn into_string(self) -> String {
+        unsafe { String::from_utf8_unchecked(self.into_bytes()) }
+    }
+
+    // Constant-time when `C` or the other side asks for it. Lengths are not
+    // secret: values of different length compare unequal early.
+    fn eq_bytes(&self, other: &[u8], constant_time: bool) -> bool {
+        if !(C::CONSTANT_TIME || constant_time) {
+            return self.as_bytes() == other;
+        }
+        #[cfg(feature = "constant-time")]
+        {
+            self.ct_eq(other).into()
+        }
+        #[cfg(not(feature = "constant-time"))]
+        {
+            let a = self.as_bytes();
+            if a.len() != other.len() { return false; }
+            let diff = a.iter().zip(other).fold(0u8, |acc, (x, y)| acc | (x ^ y));
+            core::hint::black_box(diff) == 0
+        }
+    }
+
+
+    #[cfg(feature = "constant-time")]
+    pub fn ct_eq<T: AsRef<[u8]> + ?Sized>(&self, other: &T) -> subtle::Choice {
+        subtle::ConstantTimeEq::ct_eq(self.as_bytes(), other.as_ref())
+    }
+}
+
+pub struct BoundedStrEditor<
+    'a,
+    const MIN: usize,
+    const MAX: usize,
+    const MAX_BYTES: usize,
+    L: LengthPolicy,
+    F: FormatPolicy,
+    const Z: bool,
+    C: EqPolicy,
+> {
+    target: &'a mut BoundedStr<MIN, MAX, MAX_BYTES, L, F, Z, C>,
+    work: Storage<MAX_BYTES>,
+    checkpoint: usize,
+}
+
+impl<const MIN: usize, const MAX: usize, const MAX_BYTES: usize, L: LengthPolicy, F: FormatPolicy, const Z: bool, C: EqPolicy>
+    BoundedStrEditor<'_, MIN, MAX, MAX_BYTES, L, F, Z, C>
+{
+    #[inline(always)]
+    pub fn as_str(&self) -> &str {
+        unsafe { str::from_utf8_unchecked(self.work.as_bytes()) }
+    }
+
+    pub fn push_str(&mut self, s: &str) -> Result<&mut Self, BoundedStrError> {
+        let end = self.work.as_bytes().len();
+        self.replace_range(end..end, s)
+    }
+
+    pub fn insert(&mut self, at: usize, s: &str) -> Result<&mut Self, BoundedStrError> {
+        self.replace_range(at..at, s)
+    }
+
+    pub fn remove_range(&mut self, range: Range<usize>) -> Result<&mut Self, BoundedStrError> {
+        self.replace_range(range, "")
+    }
+
+    pub fn truncate(&mut self, new_len: usize) -> Result<&mut Self, BoundedStrError> {
+        let end = self.work.as_bytes().len();
+        if new_len >= end { return Ok(self); }
+        self.replace_range(new_len..end, "")
+    }
+
+    pub fn replace_range(&mut self, range: Range<usize>, with: &str) -> Result<&mut Self, BoundedStrError> {
+        let current = self.as_str();
+        if range.start > range.end
+            || !current.is_char_boundary(range.start)
+            || !current.is_char_boundary(range.end)
+        {
+            return Err(BoundedStrError::NotCharBoundary);
+        }
+        self.work.splice::<Z>(range, with.as_bytes())?;
+        Ok(self)
+    }
+
+    pub fn retain<P: FnMut(char) -> bool>(&mut self, keep: P) -> &mut Self {
+        self.work.retain::<Z>(keep);
+        self
+    }
+
+    pub fn commit(mut self) -> Result<(), BoundedStrError> {
+        let logical_len = BoundedStr::<MIN, MAX, MAX_BYTES, L, F, Z, C>::validate(self.as_str())?;
+        core::mem::swap(&mut self.target.storage, &mut self.work);
+        self.target.set_logical_len(logical_len);
+        Ok(())
+    }
+}
+
+impl<const MIN: usize, const MAX: usize, const MAX_BYTES: usize, L: LengthPolicy, F: FormatPolicy, const Z: bool, C: EqPolicy>
+    fmt::Write for BoundedStrEditor<'_, MIN, MAX, MAX_BYTES, L, F, Z, C>
+{
+    fn write_str(&mut self, s: &str) -> fmt::Result {
+        BoundedStr::<MIN, MAX, MAX_BYTES, L, F, Z, C>::push_checked(&mut self.work, s.as_bytes(), &mut self.checkpoint)
+            .map_err(|_| fmt::Error)
+    }
+}
+
+impl<const MIN: usize, const MAX: usize, const MAX_BYTES: usize, L: LengthPolicy, F: FormatPolicy, const Z: bool, C: EqPolicy>
+    Drop for BoundedStrEditor<'_, MIN, MAX, MAX_BYTES, L, F, Z, C>
+{
+    #[inline(always)]
+    fn drop(&mut self) {
+        self.work.wipe::<Z>();
+    }
+}
+
+// Assembles a value from byte chunks, e.g. a chunked HTTP body or SAX text
+// events. A char split across chunks is held back until the next one completes
+// it. Length is checked as chunks arrive; the format policy runs in `finish()`.
+pub struct BoundedStrBuilder<
+    const MIN: usize,
+    const MAX: usize,
+    const MAX_BYTES: usize,
+    L: LengthPolicy = Bytes,
+    F: FormatPolicy = AllowAll,
+    const Z: bool = false,
+    C: EqPolicy = FastEq,
+> {
+    storage: Storage<MAX_BYTES>,
+    // Length of the UTF-8-checked prefix; at most 3 bytes of a split char follow.
+    checked: usize,
+    // Logical length of the checked prefix for `Bytes`/`Chars`/`Utf16Units`.
+    // Other policies aren't additive and are re-measured at `checkpoint`.
+    logical_len: usize,
+    checkpoint: usize,
+    _marker: PhantomData<(L, F, C)>,
+}
+
+unsafe impl<const MIN: usize, const MAX: usize, const MAX_BYTES: usize, L: LengthPolicy, F: FormatPolicy, const Z: bool, C: EqPolicy> Send for BoundedStrBuilder<MIN, MAX, MAX_BYTES, L, F, Z, C> {}
+unsafe impl<const MIN: usize, const MAX: usize, const MAX_BYTES: usize, L: LengthPolicy, F: FormatPolicy, const Z: bool, C: EqPolicy> Sync for BoundedStrBuilder<MIN, MAX, MAX_BYTES, L, F, Z, C> {}
+impl<const MIN: usize, const MAX: usize, const MAX_BYTES: usize, L: LengthPolicy, F: FormatPolicy, const Z: bool, C: EqPolicy> UnwindSafe for BoundedStrBuilder<MIN, MAX, MAX_BYTES, L, F, Z, C> {}
+impl<const MIN: usize, const MAX: usize, const MAX_BYTES: usize, L: LengthPolicy, F: FormatPolicy, const Z: bool, C: EqPolicy> RefUnwindSafe for BoundedStrBuilder<MIN, MAX, MAX_BYTES, L, F, Z, C> {}
+
+impl<const MIN: usize, const MAX: usize, const MAX_BYTES: usize, L: LengthPolicy, F: FormatPolicy, const Z: bool, C: EqPolicy> BoundedStrBuilder<MIN, MAX, MAX_BYTES, L, F, Z, C> {
+    pub fn new() -> Self {
+        Self { storage: Storage::empty(), checked: 0, logical_len: 0, checkpoint: MAX, _marker: PhantomData }
+    }
+
+    // Includes the bytes of a char that isn't complete yet.
+    #[inline(always)]
+    pub fn len_bytes(&self) -> usize {
+        self.storage.as_bytes().len()
+    }
+
+    // Logical length of the complete chars pushed so far.
+    pub fn len_logical(&self) -> usize {
+        match L::CONST_LENGTH {
+            ConstLength::Unsupported => L::logical_len(self.checked_str()),
+            _ => self.logical_len,
+        }
+    }
+
+    #[inline(always)]
+    fn checked_str(&self) -> &str {
+        unsafe { str::from_utf8_unchecked(&self.storage.as_bytes()[..self.checked]) }
+    }
+
+    // Appends `chunk`, failing early on malformed UTF-8 or once the content is
+    // too long. A rejected chunk leaves the builder as it was.
+    pub fn push_chunk(&mut self, chunk: &[u8]) -> Result<&mut Self, BoundedStrError> {
+        let old_len = self.len_bytes();
+        let new_len = old_len.saturating_add(chunk.len());
+        let limit = BoundedStr::<MIN, MAX, MAX_BYTES, L, F, Z, C>::BYTE_LIMIT;
+        if new_len > limit {
+            return Err(match L::CONST_LENGTH {
+                ConstLength::Bytes => BoundedStrError::TooLong { actual: new_len, max: MAX },
+                _ => BoundedStrError::TooManyBytes { actual: new_len, max_bytes: limit },
+            });
+        }
+        self.storage.push::<Z>(chunk)?;
+        match self.advance() {
+            Ok(()) => Ok(self),
+            Err(e) => {
+                let _ = self.storage.splice::<Z>(old_len..new_len, &[]);
+                Err(e)
+            }
+        }
+    }
+
+    #[inline(always)]
+    pub fn push_str(&mut self, s: &str) -> Result<&mut Self, BoundedStrError> {
+        self.push_chunk(s.as_bytes())
+    }
+
+    fn advance(&mut self) -> Result<(), BoundedStrError> {
+        let bytes = self.storage.as_bytes();
+        let valid = match str::from_utf8(&bytes[self.checked..]) {
+            Ok(s) => s.len(),
+            Err(e) if e.error_len().is_none() => e.valid_up_to(),
+            Err(_) => return Err(BoundedStrError::InvalidUtf8),
+        };
+        let piece = unsafe { str::from_utf8_unchecked(&bytes[self.checked..self.checked + valid]) };
+        let actual = match L::CONST_LENGTH {
+            ConstLength::Unsupported if self.checked + valid <= self.checkpoint => None,
+            ConstLength::Unsupported => Some(L::logical_len(unsafe { str::from_utf8_unchecked(&bytes[..self.checked + valid]) })),
+            _ => Some(self.logical_len + L::logical_len(piece)),
+        };
+        if let Some(actual) = actual {
+            if actual > MAX { return Err(BoundedStrError::TooLong { actual, max: MAX }); }
+            match L::CONST_LENGTH {
+                ConstLength::Unsupported => self.checkpoint = (self.checked + valid).saturating_mul(2),
+                _ => self.logical_len = actual,
+            }
+        }
+        self.checked += valid;
+        Ok(())
+    }
+
+    // Runs the full bound and format checks. Trailing bytes of an unfinished
+    // char are `InvalidUtf8`.
+    pub fn finish(mut self) -> Result<BoundedStr<MIN, MAX, MAX_BYTES, L, F, Z, C>, BoundedStrError> {
+        if self.checked != self.len_bytes() { return Err(BoundedStrError::InvalidUtf8); }
+        BoundedStr::from_storage(core::mem::replace(&mut self.storage, Storage::empty()))
+    }
+}
+
+impl<const MIN: usize, const MAX: usize, const MAX_BYTES: usize, L: LengthPolicy, F: FormatPolicy, const Z: bool, C: EqPolicy> Default for BoundedStrBuilder<MIN, MAX, MAX_BYTES, L, F, Z, C> {
+    fn default() -> Self { Self::new() }
+}
+
+impl<const MIN: usize, const MAX: usize, const MAX_BYTES: usize, L: LengthPolicy, F: FormatPolicy, const Z: bool, C: EqPolicy> fmt::Write for BoundedStrBuilder<MIN, MAX, MAX_BYTES, L, F, Z, C> {
+    fn write_str(&mut self, s: &str) -> fmt::Result {
+        self.push_str(s).map(|_| ()).map_err(|_| fmt::Error)
+    }
+}
+
+// Lengths only: the content is unchecked until `finish()`, and may be a secret.
+impl<const MIN: usize, const MAX: usize, const MAX_BYTES: usize, L: LengthPolicy, F: FormatPolicy, const Z: bool, C: EqPolicy> fmt::Debug for BoundedStrBuilder<MIN, MAX, MAX_BYTES, L, F, Z, C> {
+    fn fmt(&self, f: &mut Formatter<'_>) -> fmt::Result {
+        f.debug_struct("BoundedStrBuilder")
+            .field("len_bytes", &self.len_bytes())
+            .field("len_logical", &self.len_logical())
+            .finish()
+    }
+}
+
+impl<const MIN: usize, const MAX: usize, const MAX_BYTES: usize, L: LengthPolicy, F: FormatPolicy, const Z: bool, C: EqPolicy> Drop for BoundedStrBuilder<MIN, MAX, MAX_BYTES, L, F, Z, C> {
+    #[inline(always)]
+    fn drop(&mut self) {
+        self.storage.wipe::<Z>();
+    }
+}
+
+// `io::Write` into a `BoundedStr`. Each `write()` appends all of `buf` or
+// nothing: bound violations fail with `WriteZero` and malformed UTF-8 or format
+// violations with `InvalidData`, both wrapping the `BoundedStrError`. A char
+// split across writes is held back until it is complete.
+#[cfg(feature = "std")]
+pub struct BoundedWriter<
+    'a,
+    const MIN: usize,
+    const MAX: usize,
+    const MAX_BYTES: usize,
+    L: LengthPolicy,
+    F: FormatPolicy,
+    const Z: bool,
+    C: EqPolicy,
+> {
+    target: &'a mut BoundedStr<MIN, MAX, MAX_BYTES, L, F, Z, C>,
+    pending: [u8; 4],
+    pending_len: usize,
+}
+
+#[cfg(feature = "std")]
+impl<const MIN: usize, const MAX: usize, const MAX_BYTES: usize, L: LengthPolicy, F: FormatPolicy, const Z: bool, C: EqPolicy> BoundedWriter<'_, MIN, MAX, MAX_BYTES, L, F, Z, C> {
+    // Fails with `InvalidData` if the last write ended inside a char.
+    pub fn finish(mut self) -> std::io::Result<()> {
+        if self.pending_len != 0 {
+            self.pending_len = 0;
+            return Err(Self::io_error(BoundedStrError::InvalidUtf8));
+        }
+        Ok(())
+    }
+
+    fn io_error(e: BoundedStrError) -> std::io::Error {
+        let kind = match e {
+            BoundedStrError::TooLong { .. } | BoundedStrError::TooManyBytes { .. } => std::io::ErrorKind::WriteZero,
+            _ => std::io::ErrorKind::InvalidData,
+        };
+        std::io::Error::new(kind, e)
+    }
+
+    // Completes a char left over from the previous write in `split` first.
+    fn append(&mut self, buf: &[u8], split: &mut [u8; 4]) -> std::io::Result<usize> {
+        let mut split_len = self.pending_len;
+        split[..split_len].copy_from_slice(&self.pending[..split_len]);
+        let mut rest = buf;
+        if split_len != 0 {
+            let width = match split[0] { 0xF0.. => 4, 0xE0.. => 3, _ => 2 };
+            let take = core::cmp::min(width - split_len, rest.len());
+            split[split_len..split_len + take].copy_from_slice(&rest[..take]);
+            split_len += take;
+            rest = &rest[take..];
+            if split_len < width {
+                self.pending = *split;
+                self.pending_len = split_len;
+                return Ok(buf.len());
+            }
+        }
+        let head = str::from_utf8(&split[..split_len]).map_err(|_| Self::io_error(BoundedStrError::InvalidUtf8))?;
+        let (body, tail) = match str::from_utf8(rest) {
+            Ok(s) => (s, &[][..]),
+            Err(e) if e.error_len().is_none() => {
+                let (valid, tail) = rest.split_at(e.valid_up_to());
+                (unsafe { str::from_utf8_unchecked(valid) }, tail)
+            }
+            Err(_) => return Err(Self::io_error(BoundedStrError::InvalidUtf8)),
+        };
+        let res = if head.is_empty() {
+            self.target.try_push_str(body)
+        } else {
+            let mut editor = self.target.edit();
+            editor.push_str(head).and_then(|e| e.push_str(body)).map(|_| ()).and_then(|()| editor.commit())
+        };
+        res.map_err(Self::io_error)?;
+        self.pending[..tail.len()].copy_from_slice(tail);
+        self.pending_len = tail.len();
+        Ok(buf.len())
+    }
+}
+
+#[cfg(feature = "std")]
+impl<const MIN: usize, const MAX: usize, const MAX_BYTES: usize, L: LengthPolicy, F: FormatPolicy, const Z: bool, C: EqPolicy> std::io::Write for BoundedWriter<'_, MIN, MAX, MAX_BYTES, L, F, Z, C> {
+    fn write(&mut self, buf: &[u8]) -> std::io::Result<usize> {
+        let mut split = [0u8; 4];
+        let res = self.append(buf, &mut split);
+        wipe::<Z>(&mut split);
+        res
+    }
+
+    fn flush(&mut self) -> std::io::Result<()> {
+        Ok(())
+    }
+}
+
+#[cfg(feature = "std")]
+impl<const MIN: usize, const MAX: usize, const MAX_BYTES: usize, L: LengthPolicy, F: FormatPolicy, const Z: bool, C: EqPolicy> Drop for BoundedWriter<'_, MIN, MAX, MAX_BYTES, L, F, Z, C> {
+    #[inline(always)]
+    fn drop(&mut self) {
+        wipe::<Z>(&mut self.pending);
+    }
+}
+
+impl<
+    const MIN: usize, const MAX: usize, const MAX_BYTES: usize, L: LengthPolicy, F: FormatPolicy, const Z: bool, C: EqPolicy,
+    const MIN2: usize, const MAX2: usize, const MAX_BYTES2: usize, L2: LengthPolicy, F2: FormatPolicy, const Z2: bool, C2: EqPolicy,
+> PartialEq<BoundedStr<MIN2, MAX2, MAX_BYTES2, L2, F2, Z2, C2>> for BoundedStr<MIN, MAX, MAX_BYTES, L, F, Z, C>
+{
+    fn eq(&self, other: &BoundedStr<MIN2, MAX2, MAX_BYTES2, L2, F2, Z2, C2>) -> bool {
+        self.eq_bytes(other.as_bytes(), C2::CONSTANT_TIME)
+    }
+}
+
+#[cfg(feature = "constant-time")]
+impl<const MIN: usize, const MAX: usize, const MAX_BYTES: usize, L: LengthPolicy, F: FormatPolicy, const Z: bool, C: EqPolicy>
+    subtle::ConstantTimeEq for BoundedStr<MIN, MAX, MAX_BYTES, L, F, Z, C>
+{
+    fn ct_eq(&self, other: &Self) -> subtle::Choice {
+        BoundedStr::ct_eq(self, other)
+    }
+}
+
+#[cfg(feature = "secrecy")]
+impl<const MIN: usize, const MAX: usize, const MAX_BYTES: usize, L: LengthPolicy, F: FormatPolicy, C: EqPolicy>
+    secrecy::ExposeSecret<str> for BoundedStr<MIN, MAX, MAX_BYTES, L, F, true, C>
+{
+    fn expose_secret(&self) -> &str { self.as_str() }
+}
+
+impl<const MIN: usize, const MAX: usize, const MAX_BYTES: usize, L: LengthPolicy, F: FormatPolicy, const Z: bool, C: EqPolicy> 
+    Clone for BoundedStr<MIN, MAX, MAX_BYTES, L, F, Z, C> {
+    fn clone(&self) -> Self {
+        let storage = self.storage.clone();
+        storage.lock::<Z>();
+        Self { storage, _marker: PhantomData }
+    }
+
+    // Overwrites in place, keeping a heap destination on its existing buffer.
+    fn clone_from(&mut self, source: &Self) {
+        let end = self.len_bytes();
+        let _ = self.storage.splice::<Z>(0..end, source.as_bytes());
+        self.set_logical_len(source.len_logical());
+    }
+}
+
+impl<const MIN: usize, const MAX: usize, const MAX_BYTES: usize, L: LengthPolicy, F: FormatPolicy, const Z: bool, C: EqPolicy>
+    Default for BoundedStr<MIN, MAX, MAX_BYTES, L, F, Z, C>
+{
+    fn default() -> Self {
+        let () = Self::_EMPTY_ALLOWED;
+        Self::from_storage(Storage::empty()).expect("FormatPolicy::ACCEPTS_EMPTY must agree with check(\"\")")
+    }
+}
+
+impl<const MIN: usize, const MAX: usize, const MAX_BYTES: usize, L: LengthPolicy, F: FormatPolicy, const Z: bool, C: EqPolicy>
+    Eq for BoundedStr<MIN, MAX, MAX_BYTES, L, F, Z, C> {}
+	
+impl<const MIN: usize, const MAX: usize, const MAX_BYTES: usize, L: LengthPolicy, F: FormatPolicy, const Z: bool, C: EqPolicy>
+    PartialEq<&str> for BoundedStr<MIN, MAX, MAX_BYTES, L, F, Z, C>
+{
+    fn eq(&self, other: &&str) -> bool { self.eq_bytes(other.as_bytes(), false) }
+}
+
+impl<const MIN: usize, const MAX: usize, const MAX_BYTES: usize, L: LengthPolicy, F: FormatPolicy, const Z: bool, C: EqPolicy>
+    PartialEq<BoundedStr<MIN, MAX, MAX_BYTES, L, F, Z, C>> for &str
+{
+    fn eq(&self, other: &BoundedStr<MIN, MAX, MAX_BYTES, L, F, Z, C>) -> bool { other.eq_bytes(self.as_bytes(), false) }
+}
+
+#[cfg(feature = "alloc")]
+impl<const MIN: usize, const MAX: usize, const MAX_BYTES: usize, L: LengthPolicy, F: FormatPolicy, const Z: bool, C: EqPolicy>
+    PartialEq<String> for BoundedStr<MIN, MAX, MAX_BYTES, L, F, Z, C>
+{
+    fn eq(&self, other: &String) -> bool { self.eq_bytes(other.as_bytes(), false) }
+}
+
+#[cfg(feature = "alloc")]
+impl<const MIN: usize, const MAX: usize, const MAX_BYTES: usize, L: LengthPolicy, F: FormatPolicy, const Z: bool, C: EqPolicy>
+    PartialEq<BoundedStr<MIN, MAX, MAX_BYTES, L, F, Z, C>> for String
+{
+    fn eq(&self, other: &BoundedStr<MIN, MAX, MAX_BYTES, L, F, Z, C>) -> bool { other.eq_bytes(self.as_bytes(), false) }
+}
+
+// Only for `FastEq`: ordering compares byte by byte and stops at the first
+// difference, so constant-time types get `==` and nothing else.
+impl<const MIN: usize, const MAX: usize, const MAX_BYTES: usize, L: LengthPolicy, F: FormatPolicy, const Z: bool>
+    Ord for BoundedStr<MIN, MAX, MAX_BYTES, L, F, Z, FastEq>
+{
+    fn cmp(&self, other: &Self) -> Ordering { self.as_str().cmp(other.as_str()) }
+}
+
+impl<
+    const MIN: usize, const MAX: usize, const MAX_BYTES: usize, L: LengthPolicy, F: FormatPolicy, const Z: bool,
+    const MIN2: usize, const MAX2: usize, const MAX_BYTES2: usize, L2: LengthPolicy, F2: FormatPolicy, const Z2: bool,
+> PartialOrd<BoundedStr<MIN2, MAX2, MAX_BYTES2, L2, F2, Z2, FastEq>> for BoundedStr<MIN, MAX, MAX_BYTES, L, F, Z, FastEq>
+{
+    fn partial_cmp(&self, other: &BoundedStr<MIN2, MAX2, MAX_BYTES2, L2, F2, Z2, FastEq>) -> Option<Ordering> {
+        self.as_str().partial_cmp(other.as_str())
+    }
+}
+
+impl<const MIN: usize, const MAX: usize, const MAX_BYTES: usize, L: LengthPolicy, F: FormatPolicy, const Z: bool>
+    PartialOrd<&str> for BoundedStr<MIN, MAX, MAX_BYTES, L, F, Z, FastEq>
+{
+    fn partial_cmp(&self, other: &&str) -> Option<Ordering> { self.as_str().partial_cmp(*other) }
+}
+
+impl<const MIN: usize, const MAX: usize, const MAX_BYTES: usize, L: LengthPolicy, F: FormatPolicy, const Z: bool, C: EqPolicy> 
+    Deref for BoundedStr<MIN, MAX, MAX_BYTES, L, F, Z, C> {
+    type Target = str;
+    fn deref(&self) -> &str { self.as_str() }
+}
+
+impl<const MIN: usize, const MAX: usize, const MAX_BYTES: usize, L: LengthPolicy, F: FormatPolicy, const Z: bool, C: EqPolicy, I: SliceIndex<str>>
+    Index<I> for BoundedStr<MIN, MAX, MAX_BYTES, L, F, Z, C>
+{
+    type Output = I::Output;
+    fn index(&self, index: I) -> &I::Output { &self.as_str()[index] }
+}
+
+// Only for policies that hash like `str`, as `Borrow` requires; `KeyedHash`
+// values can't be looked up by `&str`.
+impl<const MIN: usize, const MAX: usize, const MAX_BYTES: usize, L: LengthPolicy, F: FormatPolicy, const Z: bool>
+    Borrow<str> for BoundedStr<MIN, MAX, MAX_BYTES, L, F, Z, FastEq>
+{
+    fn borrow(&self) -> &str { self.as_str() }
+}
+
+impl<const MIN: usize, const MAX: usize, const MAX_BYTES: usize, L: LengthPolicy, F: FormatPolicy, const Z: bool>
+    Borrow<str> for BoundedStr<MIN, MAX, MAX_BYTES, L, F, Z, ConstantTime>
+{
+    fn borrow(&self) -> &str { self.as_str() }
+}
+
+impl<const MIN: usize, const MAX: usize, const MAX_BYTES: usize, L: LengthPolicy, F: FormatPolicy, const Z: bool, C: EqPolicy>
+    AsRef<str> for BoundedStr<MIN, MAX, MAX_BYTES, L, F, Z, C>
+{
+    fn as_ref(&self) -> &str { self.as_str() }
+}
+
+impl<const MIN: usize, const MAX: usize, const MAX_BYTES: usize, L: LengthPolicy, F: FormatPolicy, const Z: bool, C: EqPolicy>
+    AsRef<[u8]> for BoundedStr<MIN, MAX, MAX_BYTES, L, F, Z, C>
+{
+    fn as_ref(&self) -> &[u8] { self.as_bytes() }
+}
+
+impl<const MIN: usize, const MAX: usize, const MAX_BYTES: usize, L: LengthPolicy, F: FormatPolicy, const Z: bool, C: EqPolicy>
+    TryFrom<&str> for BoundedStr<MIN, MAX, MAX_BYTES, L, F, Z, C>
+{
+    type Error = BoundedStrError;
+    fn try_from(s: &str) -> Result<Self, Self::Error> { Self::new(s) }
+}
+
+#[cfg(feature = "alloc")]
+impl<const MIN: usize, const MAX: usize, const MAX_BYTES: usize, L: LengthPolicy, F: FormatPolicy, const Z: bool, C: EqPolicy>
+    TryFrom<String> for BoundedStr<MIN, MAX, MAX_BYTES, L, F, Z, C>
+{
+    type Error = BoundedStrError;
+    fn try_from(s: String) -> Result<Self, Self::Error> {
+        let mut bytes = s.into_bytes();
+        if bytes.len() <= MAX_BYTES {
+            let res = Self::new(unsafe { str::from_utf8_unchecked(&bytes) });
+            wipe::<Z>(&mut bytes);
+            return res;
+        }
+
+        let logical_len = match Self::validate(unsafe { str::from_utf8_unchecked(&bytes) }) {
+            Ok(n) => n,
+            Err(e) => {
+                wipe::<Z>(&mut bytes);
+                return Err(e);
+            }
+        };
+        lock_vec::<Z>(&bytes);
+        Ok(Self::from_parts(Storage::heap(bytes), logical_len))
+    }
+}
+
+#[cfg(feature = "alloc")]
+impl<const MIN: usize, const MAX: usize, const MAX_BYTES: usize, L: LengthPolicy, F: FormatPolicy, const Z: bool, C: EqPolicy>
+    From<BoundedStr<MIN, MAX, MAX_BYTES, L, F, Z, C>> for String
+{
+    fn from(s: BoundedStr<MIN, MAX, MAX_BYTES, L, F, Z, C>) -> Self { s.into_string() }
+}
+
+#[cfg(feature = "arrayvec")]
+impl<const MIN: usize, const MAX: usize, const MAX_BYTES: usize, L: LengthPolicy, F: FormatPolicy, const Z: bool, C: EqPolicy, const CAP: usize>
+    TryFrom<&arrayvec::ArrayString<CAP>> for BoundedStr<MIN, MAX, MAX_BYTES, L, F, Z, C>
+{
+    type Error = BoundedStrError;
+    fn try_from(s: &arrayvec::ArrayString<CAP>) -> Result<Self, Self::Error> { Self::new(s.as_str()) }
+}
+
+// Infallible when every `ArrayString<CAP>` is valid: `AllowAll`, `MIN == 0`,
+// a length policy that never counts more units than bytes, `MAX >= CAP` and
+// `MAX_BYTES >= CAP`. Checked at compile time.
+#[cfg(feature = "arrayvec")]
+impl<const MIN: usize, const MAX: usize, const MAX_BYTES: usize, L: LengthPolicy, const Z: bool, C: EqPolicy, const CAP: usize>
+    From<arrayvec::ArrayString<CAP>> for BoundedStr<MIN, MAX, MAX_BYTES, L, AllowAll, Z, C>
+{
+    fn from(s: arrayvec::ArrayString<CAP>) -> Self {
+        const {
+            assert!(MIN == 0, "From<ArrayString> requires MIN == 0");
+            assert!(CAP <= MAX && CAP <= MAX_BYTES, "From<ArrayString> requires CAP <= MAX and CAP <= MAX_BYTES");
+            assert!(
+                matches!(L::CONST_LENGTH, ConstLength::Bytes | ConstLength::Chars | ConstLength::Utf16),
+                "From<ArrayString> requires the Bytes, Chars or Utf16Units length policy"
+            );
+        }
+        Self { storage: Storage::stack(s.as_bytes()), _marker: PhantomData }
+    }
+}
+
+#[cfg(feature = "arrayvec")]
+impl<const MIN: usize, const MAX: usize, const MAX_BYTES: usize, L: LengthPolicy, F: FormatPolicy, const Z: bool, C: EqPolicy, const CAP: usize>
+    TryFrom<&BoundedStr<MIN, MAX, MAX_BYTES, L, F, Z, C>> for arrayvec::ArrayString<CAP>
+{
+    type Error = BoundedStrError;
+    fn try_from(s: &BoundedStr<MIN, MAX, MAX_BYTES, L, F, Z, C>) -> Result<Self, Self::Error> {
+        arrayvec::ArrayString::from(s.as_str()).map_err(|_| BoundedStrError::TooManyBytes { actual: s.len_bytes(), max_bytes: CAP })
+    }
+}
+
+// The `Bytes` handle itself becomes the storage, so payloads from network
+// stacks are validated in place and never copied. Not offered for `Z = true`:
+// a buffer other handles can still read cannot be wiped.
+#[cfg(feature = "bytes")]
+impl<const MIN: usize, const MAX: usize, const MAX_BYTES: usize, L: LengthPolicy, F: FormatPolicy, C: EqPolicy>
+    BoundedStr<MIN, MAX, MAX_BYTES, L, F, false, C>
+{
+    pub fn try_from_bytes(bytes: bytes::Bytes) -> Result<Self, BoundedStrError> {
+        let s = utf8_str(&bytes).ok_or(BoundedStrError::InvalidUtf8)?;
+        let logical_len = Self::validate(s)?;
+        Ok(Self::from_parts(Storage::Shared(Spilled::new(bytes)), logical_len))
+    }
+}
+
+#[cfg(feature = "bytes")]
+impl<const MIN: usize, const MAX: usize, const MAX_BYTES: usize, L: LengthPolicy, F: FormatPolicy, C: EqPolicy>
+    From<BoundedStr<MIN, MAX, MAX_BYTES, L, F, false, C>> for bytes::Bytes
+{
+    fn from(mut s: BoundedStr<MIN, MAX, MAX_BYTES, L, F, false, C>) -> Self {
+        if let Storage::Shared(b) = &mut s.storage { return core::mem::take(&mut b.bytes); }
+        bytes::Bytes::from(s.into_bytes())
+    }
+}
+
+// Heap-allocated strings hand their buffer over in both directions.
+#[cfg(feature = "compact_str")]
+impl<const MIN: usize, const MAX: usize, const MAX_BYTES: usize, L: LengthPolicy, F: FormatPolicy, const Z: bool, C: EqPolicy>
+    TryFrom<compact_str::CompactString> for BoundedStr<MIN, MAX, MAX_BYTES, L, F, Z, C>
+{
+    type Error = BoundedStrError;
+    fn try_from(s: compact_str::CompactString) -> Result<Self, Self::Error> {
+        if !s.is_heap_allocated() { return Self::new(&s); }
+        Self::try_from(s.into_string())
+    }
+}
+
+#[cfg(feature = "compact_str")]
+impl<const MIN: usize, const MAX: usize, const MAX_BYTES: usize, L: LengthPolicy, F: FormatPolicy, const Z: bool, C: EqPolicy>
+    From<BoundedStr<MIN, MAX, MAX_BYTES, L, F, Z, C>> for compact_str::CompactString
+{
+    fn from(s: BoundedStr<MIN, MAX, MAX_BYTES, L, F, Z, C>) -> Self {
+        if !s.is_heap() { return compact_str::CompactString::new(s.as_str()); }
+        compact_str::CompactString::from(s.into_string())
+    }
+}
+
+// `SmolStr` shares its heap buffer behind an `Arc`, so these always copy.
+#[cfg(feature = "smol_str")]
+impl<const MIN: usize, const MAX: usize, const MAX_BYTES: usize, L: LengthPolicy, F: FormatPolicy, const Z: bool, C: EqPolicy>
+    TryFrom<&smol_str::SmolStr> for BoundedStr<MIN, MAX, MAX_BYTES, L, F, Z, C>
+{
+    type Error = BoundedStrError;
+    fn try_from(s: &smol_str::SmolStr) -> Result<Self, Self::Error> { Self::new(s.as_str()) }
+}
+
+#[cfg(feature = "smol_str")]
+impl<const MIN: usize, const MAX: usize, const MAX_BYTES: usize, L: LengthPolicy, F: FormatPolicy, const Z: bool, C: EqPolicy>
+    From<&BoundedStr<MIN, MAX, MAX_BYTES, L, F, Z, C>> for smol_str::SmolStr
+{
+    fn from(s: &BoundedStr<MIN, MAX, MAX_BYTES, L, F, Z, C>) -> Self { smol_str::SmolStr::new(s.as_str()) }
+}
+
+#[cfg(feature = "heapless")]
+impl<const MIN: usize, const MAX: usize, const MAX_BYTES: usize, L: LengthPolicy, F: FormatPolicy, const Z: bool, C: EqPolicy, const N: usize, LenT: heapless::LenType>
+    TryFrom<&heapless::String<N, LenT>> for BoundedStr<MIN, MAX, MAX_BYTES, L, F, Z, C>
+{
+    type Error = BoundedStrError;
+    fn try_from(s: &heapless::String<N, LenT>) -> Result<Self, Self::Error> { Self::new(s.as_str()) }
+}
+
+#[cfg(feature = "heapless")]
+impl<const MIN: usize, const MAX: usize, const MAX_BYTES: usize, L: LengthPolicy, F: FormatPolicy, const Z: bool, C: EqPolicy>
+    BoundedStr<MIN, MAX, MAX_BYTES, L, F, Z, C>
+{
+    // Copies into a `heapless::String<N>`; fails with `TooManyBytes` if the
+    // content doesn't fit `N` bytes.
+    pub fn to_heapless<const N: usize>(&self) -> Result<heapless::String<N>, BoundedStrError> {
+        let mut out = heapless::String::new();
+        out.push_str(self.as_str())
+            .map_err(|_| BoundedStrError::TooManyBytes { actual: self.len_bytes(), max_bytes: N })?;
+        Ok(out)
+    }
+}
+
+impl<const MIN: usize, const MAX: usize, const MAX_BYTES: usize, L: LengthPolicy, F: FormatPolicy, const Z: bool, C: EqPolicy>
+    FromStr for BoundedStr<MIN, MAX, MAX_BYTES, L, F, Z, C>
+{
+    type Err = BoundedStrError;
+    fn from_str(s: &str) -> Result<Self, Self::Err> { Self::new(s) }
+}
+
+impl<const MIN: usize, const MAX: usize, const MAX_BYTES: usize, L: LengthPolicy, F: FormatPolicy, const Z: bool, C: EqPolicy>
+    Add<&str> for BoundedStr<MIN, MAX, MAX_BYTES, L, F, Z, C>
+{
+    type Output = Result<Self, BoundedStrError>;
+    fn add(mut self, rhs: &str) -> Self::Output {
+        self.try_push_str(rhs)?;
+        Ok(self)
+    }
+}
+
+impl<const MIN: usize, const MAX: usize, const MAX_BYTES: usize, L: LengthPolicy, F: FormatPolicy, const Z: bool, C: EqPolicy>
+    Add<&str> for &BoundedStr<MIN, MAX, MAX_BYTES, L, F, Z, C>
+{
+    type Output = Result<BoundedStr<MIN, MAX, MAX_BYTES, L, F, Z, C>, BoundedStrError>;
+    fn add(self, rhs: &str) -> Self::Output { self.try_concat(rhs) }
+}
+
+impl<const MIN: usize, const MAX: usize, const MAX_BYTES: usize, L: LengthPolicy, F: FormatPolicy, const Z: bool, C: EqPolicy>
+    fmt::Write for BoundedStr<MIN, MAX, MAX_BYTES, L, F, Z, C>
+{
+    fn write_str(&mut self, s: &str) -> fmt::Result {
+        self.try_push_str(s).map_err(|_| fmt::Error)
+    }
+
+    fn write_fmt(&mut self, args: fmt::Arguments<'_>) -> fmt::Result {
+        let mut editor = self.edit();
+        fmt::Write::write_fmt(&mut editor, args)?;
+        editor.commit().map_err(|_| fmt::Error)
+    }
+}
+
+impl<const MIN: usize, const MAX: usize, const MAX_BYTES: usize, L: LengthPolicy, F: FormatPolicy, const Z: bool, C: EqPolicy>
+    Hash for BoundedStr<MIN, MAX, MAX_BYTES, L, F, Z, C>
+{
+    fn hash<H: Hasher>(&self, state: &mut H) { C::hash(self.as_str(), state) }
+}
+
+impl<const MIN: usize, const MAX: usize, const MAX_BYTES: usize, L: LengthPolicy, F: FormatPolicy, const Z: bool, C: EqPolicy>
+    Display for BoundedStr<MIN, MAX, MAX_BYTES, L, F, Z, C>
+{
+    fn fmt(&self, f: &mut Formatter<'_>) -> fmt::Result {
+        f.pad(if Z { REDACTED } else { self.as_str() })
+    }
+}
+
+// Zeroizing values are secrets: neither the content nor its length is printed.
+impl<const MIN: usize, const MAX: usize, const MAX_BYTES: usize, L: LengthPolicy, F: FormatPolicy, const Z: bool, C: EqPolicy>
+    fmt::Debug for BoundedStr<MIN, MAX, MAX_BYTES, L, F, Z, C>
+{
+    fn fmt(&self, f: &mut Formatter<'_>) -> fmt::Result {
+        if Z {
+            return f.debug_tuple("BoundedStr").field(&format_args!("{REDACTED}")).finish();
+        }
+        f.debug_struct("BoundedStr")
+            .field("value", &self.as_str())
+            .field("len_bytes", &self.len_bytes())
+            .field("len_logical", &self.len_logical())
+            .finish()
+    }
+}
+
+impl<const MIN: usize, const MAX: usize, const MAX_BYTES: usize, L: LengthPolicy, F: FormatPolicy, const Z: bool, C: EqPolicy> 
+    Drop for BoundedStr<MIN, MAX, MAX_BYTES, L, F, Z, C> 
+{
+    #[inline(always)]
+    fn drop(&mut self) {
+        self.storage.wipe::<Z>();
+    }
+}
+
+
+// Wipes the whole buffer, spare capacity included, and leaves the value empty.
+// That is below `MIN` for most types, so treat a zeroized value as spent.
+#[cfg(feature = "zeroize")]
+impl<const MIN: usize, const MAX: usize, const MAX_BYTES: usize, L: LengthPolicy, F: FormatPolicy, const Z: bool, C: EqPolicy>
+    zeroize::Zeroize for BoundedStr<MIN, MAX, MAX_BYTES, L, F, Z, C>
+{
+    fn zeroize(&mut self) {
+        self.storage.wipe::<true>();
+        self.storage = Storage::empty();
+    }
+}
+
+#[cfg(feature = "zeroize")]
+impl<const MIN: usize, const MAX: usize, const MAX_BYTES: usize, L: LengthPolicy, F: FormatPolicy, C: EqPolicy>
+    zeroize::ZeroizeOnDrop for BoundedStr<MIN, MAX, MAX_BYTES, L, F, true, C>
+{
+}
+
+#[cfg(feature = "serde")]
+impl<'de, const MIN: usize, const MAX: usize, const MAX_BYTES: usize, L: LengthPolicy, F: FormatPolicy, const Z: bool, C: EqPolicy> 
+    ::serde::Deserialize<'de> for BoundedStr<MIN, MAX, MAX_BYTES, L, F, Z, C> 
+{
+    fn deserialize<D>(deserializer: D) -> Result<Self, D::Error>
+    where
+        D: ::serde::Deserializer<'de>,
+    {
+        deserializer.deserialize_string(BoundedStrVisitor(PhantomData))
+    }
+}
+
+#[cfg(feature = "serde")]
+struct BoundedStrVisitor<const MIN: usize, const MAX: usize, const MAX_BYTES: usize, L, F, const Z: bool, C>(
+    PhantomData<(L, F, C)>,
+);
+
+#[cfg(feature = "serde")]
+impl<'de, const MIN: usize, const MAX: usize, const MAX_BYTES: usize, L: LengthPolicy, F: FormatPolicy, const Z: bool, C: EqPolicy>
+    ::serde::de::Visitor<'de> for BoundedStrVisitor<MIN, MAX, MAX_BYTES, L, F, Z, C>
+{
+    type Value = BoundedStr<MIN, MAX, MAX_BYTES, L, F, Z, C>;
+
+    fn expecting(&self, f: &mut Formatter<'_>) -> fmt::Result {
+        write!(f, "a string of length {}..={}", MIN, MAX)
+    }
+
+    fn visit_str<E: ::serde::de::Error>(self, v: &str) -> Result<Self::Value, E> {
+        BoundedStr::new(v).map_err(|e| E::custom(DeError::<L, F>(e, PhantomData)))
+    }
+
+    fn visit_borrowed_str<E: ::serde::de::Error>(self, v: &'de str) -> Result<Self::Value, E> {
+        self.visit_str(v)
+    }
+
+    fn visit_string<E: ::serde::de::Error>(self, v: String) -> Result<Self::Value, E> {
+        BoundedStr::try_from(v).map_err(|e| E::custom(DeError::<L, F>(e, PhantomData)))
+    }
+}
+
+// Serde error message naming the length unit and format policy of the target type.
+#[cfg(feature = "serde")]
+struct DeError<L, F>(BoundedStrError, PhantomData<(L, F)>);
+
+#[cfg(feature = "serde")]
+impl<L: LengthPolicy, F: FormatPolicy> Display for DeError<L, F> {
+    fn fmt(&self, f: &mut Formatter<'_>) -> fmt::Result {
+        let msg = self.0.message();
+        match self.0 {
+            BoundedStrError::TooShort { actual, min } => write!(f, "{}: {} {}, min {}", msg, actual, L::UNIT, min),
+            BoundedStrError::TooLong { actual, max } => write!(f, "{}: {} {}, max {}", msg, actual, L::UNIT, max),
+            BoundedStrError::TooManyBytes { actual, max_bytes } => write!(f, "{}: {} bytes, max {}", msg, actual, max_bytes),
+            BoundedStrError::InvalidContent { .. } => write!(f, "{} (policy {})", self.0, F::name()),
+            e => Display::fmt(&e, f),
+        }
+    }
+}
+
+#[cfg(feature = "serde")]
+impl<const MIN: usize, const MAX: usize, const MAX_BYTES: usize, L: LengthPolicy, F: FormatPolicy, const Z: bool, C: EqPolicy> 
+    ::serde::Serialize for BoundedStr<MIN, MAX, MAX_BYTES, L, F, Z, C> 
+{
+    fn serialize<S>(&self, serializer: S) -> Result<S::Ok, S::Error>
+    where
+        S: ::serde::Serializer,
+    {
+        let () = Self::_SERIALIZE_ALLOWED;
+        serializer.serialize_str(self.as_str())
+    }
+}
+
+#[cfg(feature = "borsh")]
+impl<const MIN: usize, const MAX: usize, const MAX_BYTES: usize, L: LengthPolicy, F: FormatPolicy, const Z: bool, C: EqPolicy>
+    borsh::BorshSerialize for BoundedStr<MIN, MAX, MAX_BYTES, L, F, Z, C>
+{
+    fn serialize<W: borsh::io::Write>(&self, writer: &mut W) -> borsh::io::Result<()> {
+        let () = Self::_SERIALIZE_ALLOWED;
+        borsh::BorshSerialize::serialize(self.as_str(), writer)
+    }
+}
+
+#[cfg(feature = "borsh")]
+impl<const MIN: usize, const MAX: usize, const MAX_BYTES: usize, L: LengthPolicy, F: FormatPolicy, const Z: bool, C: EqPolicy>
+    borsh::BorshDeserialize for BoundedStr<MIN, MAX, MAX_BYTES, L, F, Z, C>
+{
+    // The `u32` length prefix is checked against `BYTE_LIMIT` before anything
+    // is allocated, so a forged prefix can't make the decoder reserve or read
+    // more than the longest valid value.
+    fn deserialize_reader<R: borsh::io::Read>(reader: &mut R) -> borsh::io::Result<Self> {
+        use alloc::string::ToString;
+
+        let invalid = |e: BoundedStrError| borsh::io::Error::new(borsh::io::ErrorKind::InvalidData, e.to_string());
+        let len = u32::deserialize_reader(reader)? as usize;
+        if len > Self::BYTE_LIMIT {
+            return Err(invalid(match L::CONST_LENGTH {
+                ConstLength::Bytes => BoundedStrError::TooLong { actual: len, max: MAX },
+                _ => BoundedStrError::TooManyBytes { actual: len, max_bytes: Self::BYTE_LIMIT },
+            }));
+        }
+        let mut bytes = alloc::vec![0; len];
+        if let Err(e) = reader.read_exact(&mut bytes) {
+            wipe::<Z>(&mut bytes);
+            return Err(e);
+        }
+        let res = match utf8_string(bytes) {
+            Ok(s) => Self::try_from(s),
+            Err(mut bytes) => {
+                wipe::<Z>(&mut bytes);
+                Err(BoundedStrError::InvalidUtf8)
+            }
+        };
+        res.map_err(invalid)
+    }
+}
+
+// `minLength`/`maxLength` for schema generators, which count code points.
+#[cfg(any(feature = "schemars", feature = "utoipa"))]
+impl<const MIN: usize, const MAX: usize, const MAX_BYTES: usize, L: LengthPolicy, F: FormatPolicy, const Z: bool, C: EqPolicy>
+    BoundedStr<MIN, MAX, MAX_BYTES, L, F, Z, C>
+{
+    fn code_point_range() -> (Option<usize>, Option<usize>) {
+        // A char takes at most 4 bytes.
+        let (min, max) = match L::CONST_LENGTH {
+            ConstLength::Chars => (MIN, MAX),
+            ConstLength::Bytes => (MIN.div_ceil(4), MAX),
+            // A char takes 1 or 2 UTF-16 units.
+            ConstLength::Utf16 => (MIN.div_ceil(2), MAX),
+            ConstLength::Unsupported => return (None, None),
+        };
+        ((min > 0).then_some(min), Some(max))
+    }
+}
+
+// Logged as `alice (5/16)`: the payload plus logical length and `MAX`.
+#[cfg(feature = "defmt")]
+impl<const MIN: usize, const MAX: usize, const MAX_BYTES: usize, L: LengthPolicy, F: FormatPolicy, const Z: bool, C: EqPolicy>
+    defmt::Format for BoundedStr<MIN, MAX, MAX_BYTES, L, F, Z, C>
+{
+    fn format(&self, f: defmt::Formatter) {
+        if Z {
+            return defmt::write!(f, "{=str}", REDACTED);
+        }
+        defmt::write!(f, "{=str} ({=usize}/{=usize})", self.as_str(), self.len_logical(), MAX)
+    }
+}
+
+// `uwrite!` output for firmware without `core::fmt`, matching `Display` and
+// `Debug`, redaction included.
+#[cfg(feature = "ufmt")]
+impl<const MIN: usize, const MAX: usize, const MAX_BYTES: usize, L: LengthPolicy, F: FormatPolicy, const Z: bool, C: EqPolicy>
+    ufmt::uDisplay for BoundedStr<MIN, MAX, MAX_BYTES, L, F, Z, C>
+{
+    fn fmt<W: ufmt::uWrite + ?Sized>(&self, f: &mut ufmt::Formatter<'_, W>) -> Result<(), W::Error> {
+        f.write_str(if Z { REDACTED } else { self.as_str() })
+    }
+}
+
+#[cfg(feature = "ufmt")]
+impl<const MIN: usize, const MAX: usize, const MAX_BYTES: usize, L: LengthPolicy, F: FormatPolicy, const Z: bool, C: EqPolicy>
+    ufmt::uDebug for BoundedStr<MIN, MAX, MAX_BYTES, L, F, Z, C>
+{
+    fn fmt<W: ufmt::uWrite + ?Sized>(&self, f: &mut ufmt::Formatter<'_, W>) -> Result<(), W::Error> {
+        // ufmt has no `uDebug` for `str`, so escape it the way `core::fmt` does.
+        struct Quoted<'a>(&'a str);
+        impl ufmt::uDebug for Quoted<'_> {
+            fn fmt<W: ufmt::uWrite + ?Sized>(&self, f: &mut ufmt::Formatter<'_, W>) -> Result<(), W::Error> {
+                f.write_char('"')?;
+                for c in self.0.chars() {
+                    if c == '\'' {
+                        f.write_char(c)?;
+                        continue;
+                    }
+                    for e in c.escape_debug() { f.write_char(e)?; }
+                }
+                f.write_char('"')
+            }
+        }
+
+        // Unquoted, like `format_args!` in the `core::fmt` impl.
+        struct Redacted;
+        impl ufmt::uDebug for Redacted {
+            fn fmt<W: ufmt::uWrite + ?Sized>(&self, f: &mut ufmt::Formatter<'_, W>) -> Result<(), W::Error> {
+                f.write_str(REDACTED)
+            }
+        }
+
+        if Z {
+            return f.debug_tuple("BoundedStr")?.field(&Redacted)?.finish();
+        }
+        f.debug_struct("BoundedStr")?
+            .field("value", &Quoted(self.as_str()))?
+            .field("len_bytes", &self.len_bytes())?
+            .field("len_logical", &self.len_logical())?
+            .finish()
+    }
+}
+
+#[cfg(feature = "schemars")]
+impl<const MIN: usize, const MAX: usize, const MAX_BYTES: usize, L: LengthPolicy, F: FormatPolicy, const Z: bool, C: EqPolicy>
+    schemars::JsonSchema for BoundedStr<MIN, MAX, MAX_BYTES, L, F, Z, C>
+{
+    fn inline_schema() -> bool { true }
+
+    fn schema_name() -> alloc::borrow::Cow<'static, str> { "BoundedStr".into() }
+
+    fn schema_id() -> alloc::borrow::Cow<'static, str> {
+        alloc::format!("bounded_str::BoundedStr<{}, {}, {}, {}>", MIN, MAX, core::any::type_name::<L>(), F::name()).into()
+    }
+
+    fn json_schema(_: &mut schemars::SchemaGenerator) -> schemars::Schema {
+        let mut schema = schemars::json_schema!({ "type": "string" });
+        let (min, max) = Self::code_point_range();
+        if let Some(min) = min {
+            schema.insert("minLength".into(), min.into());
+        }
+        if let Some(max) = max {
+            schema.insert("maxLength".into(), max.into());
+        }
+        if let Some(pattern) = F::json_pattern() {
+            schema.insert("pattern".into(), pattern.into());
+        }
+        schema
+    }
+}
+
+#[cfg(feature = "utoipa")]
+impl<const MIN: usize, const MAX: usize, const MAX_BYTES: usize, L: LengthPolicy, F: FormatPolicy, const Z: bool, C: EqPolicy>
+    utoipa::PartialSchema for BoundedStr<MIN, MAX, MAX_BYTES, L, F, Z, C>
+{
+    fn schema() -> utoipa::openapi::RefOr<utoipa::openapi::schema::Schema> {
+        let (min, max) = Self::code_point_range();
+        utoipa::openapi::ObjectBuilder::new()
+            .schema_type(utoipa::openapi::schema::Type::String)
+            .min_length(min)
+            .max_length(max)
+            .pattern(F::json_pattern())
+            .into()
+    }
+}
+
+#[cfg(feature = "utoipa")]
+impl<const MIN: usize, const MAX: usize, const MAX_BYTES: usize, L: LengthPolicy, F: FormatPolicy, const Z: bool, C: EqPolicy>
+    utoipa::ToSchema for BoundedStr<MIN, MAX, MAX_BYTES, L, F, Z, C>
+{
+    // Component names must differ per bounds and policy, e.g. `BoundedStr_3_16_Chars_AsciiOnly`
+    // or `BoundedStr_1_8_Bytes_And_AsciiOnly_NoNul`.
+    fn name() -> alloc::borrow::Cow<'static, str> {
+        let mut key = alloc::format!("BoundedStr_{}_{}", MIN, MAX);
+        push_component_key(&mut key, core::any::type_name::<L>());
+        push_component_key(&mut key, F::name());
+        key.into()
+    }
+}
+
+// Appends a type name as `_`-separated `[A-Za-z0-9_]` pieces, keeping only the
+// last segment of each path: `a::And<a::AsciiOnly, b::Sku>` -> `_And_AsciiOnly_Sku`.
+#[cfg(feature = "utoipa")]
+fn push_component_key(key: &mut String, type_name: &str) {
+    for path in type_name.split(|c: char| !(c.is_ascii_alphanumeric() || c == '_' || c == ':')) {
+        let ident = path.rsplit("::").next().unwrap_or(path);
+        if !ident.is_empty() {
+            key.push('_');
+            key.push_str(ident);
+        }
+    }
+}
+
+#[cfg(any(feature = "arbitrary", feature = "quickcheck"))]
+impl<const MIN: usize, const MAX: usize, const MAX_BYTES: usize, L: LengthPolicy, F: FormatPolicy, const Z: bool, C: EqPolicy>
+    BoundedStr<MIN, MAX, MAX_BYTES, L, F, Z, C>
+{
+    // Fills `target` logical units with chars from `next`, substituting an ASCII
+    // letter when a char would overshoot. `next` receives whether to stay ASCII.
+    fn generate<E>(target: usize, mut next: impl FnMut(bool) -> Result<char, E>) -> Result<Storage<MAX_BYTES>, E> {
+        let ascii = matches!(F::CONST_FORMAT, ConstFormat::AsciiOnly | ConstFormat::Charset(_));
+        let mut work = Storage::<MAX_BYTES>::empty();
+        let mut len = 0;
+
+        // Bounded so that length policies which can stall cannot loop forever.
+        for _ in 0..target.saturating_mul(2) {
+            if len >= target { break; }
+            let c = match next(ascii) {
+                Ok(c) => c,
+                Err(e) => { work.wipe::<Z>(); return Err(e); }
+            };
+            let fallback = char::from(b'a' + (c as u32 % 26) as u8);
+
+            let mut grew = false;
+            for c in [c, fallback] {
+                let start = work.as_bytes().len();
+                if work.push::<Z>(c.encode_utf8(&mut [0u8; 4]).as_bytes()).is_err() { break; }
+                let next = L::logical_len(unsafe { str::from_utf8_unchecked(work.as_bytes()) });
+                if next <= target {
+                    len = next;
+                    grew = true;
+                    break;
+                }
+                let _ = work.splice::<Z>(start..work.as_bytes().len(), b"");
+            }
+            if !grew { break; }
+        }
+        Ok(work)
+    }
+}
+
+// Builds a value of random logical length in `MIN..=MAX`. Chars are drawn from
+// ASCII for `AsciiOnly`; other policies reject non-matching output with
+// `IncorrectFormat`, so fuzzers should prefer the built-in policies.
+#[cfg(feature = "arbitrary")]
+impl<'a, const MIN: usize, const MAX: usize, const MAX_BYTES: usize, L: LengthPolicy, F: FormatPolicy, const Z: bool, C: EqPolicy>
+    arbitrary::Arbitrary<'a> for BoundedStr<MIN, MAX, MAX_BYTES, L, F, Z, C>
+{
+    fn arbitrary(u: &mut arbitrary::Unstructured<'a>) -> arbitrary::Result<Self> {
+        let target = u.int_in_range(MIN..=MAX)?;
+        let work = Self::generate(target, |ascii| match ascii {
+            true => u.int_in_range(0u8..=0x7F).map(char::from),
+            false => u.arbitrary::<char>(),
+        })?;
+        Self::from_storage(work).map_err(|_| arbitrary::Error::IncorrectFormat)
+    }
+}
+
+// Lengths grow with `Gen::size()`. Custom policies are retried a bounded number
+// of times; generation panics if none of the attempts is accepted.
+#[cfg(feature = "quickcheck")]
+impl<const MIN: usize, const MAX: usize, const MAX_BYTES: usize, L: LengthPolicy + 'static, F: FormatPolicy + 'static, const Z: bool, C: EqPolicy + 'static>
+    quickcheck::Arbitrary for BoundedStr<MIN, MAX, MAX_BYTES, L, F, Z, C>
+{
+    fn arbitrary(g: &mut quickcheck::Gen) -> Self {
+        let span = MAX.saturating_sub(MIN).min(g.size());
+        for _ in 0..100 {
+            let target = MIN + usize::arbitrary(g) % (span + 1);
+            let work = Self::generate(target, |ascii| Ok::<_, core::convert::Infallible>(match ascii {
+                true => char::from(u8::arbitrary(g) & 0x7F),
+                false => char::arbitrary(g),
+            }));
+            let Ok(work) = work;
+            if let Ok(value) = Self::from_storage(work) {
+                return value;
+            }
+        }
+        panic!("no generated string was accepted by {}", F::name());
+    }
+
+    // Drops chars while staying at or above `MIN`: first down to `MIN` units, then
+    // the second half, then one char at a time from the end.
+    fn shrink(&self) -> alloc::boxed::Box<dyn Iterator<Item = Self>> {
+        let s = String::from(self.as_str());
+        let chars: Vec<Range<usize>> = s.char_indices().map(|(i, c)| i..i + c.len_utf8()).collect();
+
+        let mut cuts = alloc::vec![L::byte_offset(&s, MIN)..s.len()];
+        if let Some(mid) = chars.get(chars.len() / 2) {
+            cuts.push(mid.start..s.len());
+        }
+        cuts.extend(chars.into_iter().rev());
+
+        alloc::boxed::Box::new(cuts.into_iter().filter(|cut| !cut.is_empty()).filter_map(move |cut| {
+            Self::new(&[&s[..cut.start], &s[cut.end..]].concat()).ok()
+        }))
+    }
+}
+
+#[cfg(feature = "proptest")]
+impl<const MIN: usize, const MAX: usize, const MAX_BYTES: usize, L: LengthPolicy + 'static, F: FormatPolicy + 'static, const Z: bool, C: EqPolicy + 'static>
+    BoundedStr<MIN, MAX, MAX_BYTES, L, F, Z, C>
+{
+    // Shrinkable strategy over valid values. ASCII strings of exactly MIN and MAX
+    // units are generated on purpose; other candidates that break the bounds or
+    // the policy are filtered out.
+    pub fn arbitrary_strategy() -> proptest::strategy::BoxedStrategy<Self> {
+        use proptest::{collection::vec, prelude::*};
+
+        let ascii = proptest::char::range('\0', '\x7F');
+        let chars = match F::CONST_FORMAT {
+            ConstFormat::AsciiOnly | ConstFormat::Charset(_) => ascii.clone().boxed(),
+            _ => any::<char>().boxed(),
+        };
+
+        prop_oneof![
+            1 => vec(ascii.clone(), MIN),
+            1 => vec(ascii, MAX),
+            8 => vec(chars, MIN..=MAX),
+        ]
+        .prop_filter_map("out of bounds or rejected by the format policy", |chars| {
+            Self::new_truncate(&chars.into_iter().collect::<String>()).ok()
+        })
+        .boxed()
+    }
+}
+
+#[cfg(feature = "proptest")]
+impl<const MIN: usize, const MAX: usize, const MAX_BYTES: usize, L: LengthPolicy + 'static, F: FormatPolicy + 'static, const Z: bool, C: EqPolicy + 'static>
+    proptest::arbitrary::Arbitrary for BoundedStr<MIN, MAX, MAX_BYTES, L, F, Z, C>
+{
+    type Parameters = ();
+    type Strategy = proptest::strategy::BoxedStrategy<Self>;
+
+    fn arbitrary_with(_: ()) -> Self::Strategy { Self::arbitrary_strategy() }
+}
+
+// Archived form of `BoundedStr`. `CheckBytes` re-runs the bound and format
+// checks, so a validated archive can be read in place without deserializing.
+#[cfg(feature = "rkyv")]
+#[repr(transparent)]
+pub struct ArchivedBoundedStr<const MIN: usize, const MAX: usize, const MAX_BYTES: usize, L, F, const Z: bool, C> {
+    inner: rkyv::string::ArchivedString,
+    _marker: PhantomData<(L, F, C)>,
+}
+
+#[cfg(feature = "rkyv")]
+impl<const MIN: usize, const MAX: usize, const MAX_BYTES: usize, L, F, const Z: bool, C: EqPolicy>
+    ArchivedBoundedStr<MIN, MAX, MAX_BYTES, L, F, Z, C>
+{
+    #[inline(always)]
+    pub fn as_str(&self) -> &str { self.inner.as_str() }
+}
+
+#[cfg(feature = "rkyv")]
+impl<const MIN: usize, const MAX: usize, const MAX_BYTES: usize, L, F, const Z: bool, C: EqPolicy>
+    Deref for ArchivedBoundedStr<MIN, MAX, MAX_BYTES, L, F, Z, C>
+{
+    type Target = str;
+    #[inline(always)]
+    fn deref(&self) -> &str { self.as_str() }
+}
+
+#[cfg(feature = "rkyv")]
+impl<const MIN: usize, const MAX: usize, const MAX_BYTES: usize, L, F, const Z: bool, C: EqPolicy>
+    fmt::Debug for ArchivedBoundedStr<MIN, MAX, MAX_BYTES, L, F, Z, C>
+{
+    fn fmt(&self, f: &mut Formatter<'_>) -> fmt::Result {
+        if Z { f.write_str(REDACTED) } else { fmt::Debug::fmt(self.as_str(), f) }
+    }
+}
+
+#[cfg(feature = "rkyv")]
+impl<const MIN: usize, const MAX: usize, const MAX_BYTES: usize, L, F, const Z: bool, C: EqPolicy>
+    Display for ArchivedBoundedStr<MIN, MAX, MAX_BYTES, L, F, Z, C>
+{
+    fn fmt(&self, f: &mut Formatter<'_>) -> fmt::Result {
+        f.pad(if Z { REDACTED } else { self.as_str() })
+    }
+}
+
+#[cfg(feature = "rkyv")]
+impl<const MIN: usize, const MAX: usize, const MAX_BYTES: usize, L, F, const Z: bool, C: EqPolicy>
+    PartialEq<str> for ArchivedBoundedStr<MIN, MAX, MAX_BYTES, L, F, Z, C>
+{
+    fn eq(&self, other: &str) -> bool { self.as_str() == other }
+}
+
+#[cfg(feature = "rkyv")]
+unsafe impl<const MIN: usize, const MAX: usize, const MAX_BYTES: usize, L, F, const Z: bool, C: EqPolicy>
+    rkyv::Portable for ArchivedBoundedStr<MIN, MAX, MAX_BYTES, L, F, Z, C>
+{
+}
+
+#[cfg(feature = "rkyv")]
+unsafe impl<Ctx, const MIN: usize, const MAX: usize, const MAX_BYTES: usize, L: LengthPolicy, F: FormatPolicy, const Z: bool, C: EqPolicy>
+    rkyv::bytecheck::CheckBytes<Ctx> for ArchivedBoundedStr<MIN, MAX, MAX_BYTES, L, F, Z, C>
+where
+    Ctx: rkyv::rancor::Fallible + ?Sized,
+    Ctx::Error: rkyv::rancor::Source,
+    rkyv::string::ArchivedString: rkyv::bytecheck::CheckBytes<Ctx>,
+{
+    unsafe fn check_bytes(value: *const Self, context: &mut Ctx) -> Result<(), Ctx::Error> {
+        use rkyv::rancor::Source;
+
+        unsafe { rkyv::string::ArchivedString::check_bytes(value.cast(), context)? };
+        let s = unsafe { &*value }.as_str();
+        BoundedStr::<MIN, MAX, MAX_BYTES, L, F, Z, C>::validate(s).map(|_| ()).map_err(Ctx::Error::new)
+    }
+}
+
+#[cfg(feature = "rkyv")]
+impl<const MIN: usize, const MAX: usize, const MAX_BYTES: usize, L: LengthPolicy, F: FormatPolicy, const Z: bool, C: EqPolicy>
+    rkyv::Archive for BoundedStr<MIN, MAX, MAX_BYTES, L, F, Z, C>
+{
+    type Archived = ArchivedBoundedStr<MIN, MAX, MAX_BYTES, L, F, Z, C>;
+    type Resolver = rkyv::string::StringResolver;
+
+    fn resolve(&self, resolver: Self::Resolver, out: rkyv::Place<Self::Archived>) {
+        // `ArchivedBoundedStr` is a transparent wrapper around `ArchivedString`.
+        let out = unsafe { out.cast_unchecked::<rkyv::string::ArchivedString>() };
+        rkyv::string::ArchivedString::resolve_from_str(self.as_str(), resolver, out);
+    }
+}
+
+#[cfg(feature = "rkyv")]
+impl<S, const MIN: usize, const MAX: usize, const MAX_BYTES: usize, L: LengthPolicy, F: FormatPolicy, const Z: bool, C: EqPolicy>
+    rkyv::Serialize<S> for BoundedStr<MIN, MAX, MAX_BYTES, L, F, Z, C>
+where
+    S: rkyv::rancor::Fallible + ?Sized,
+    S::Error: rkyv::rancor::Source,
+    str: rkyv::SerializeUnsized<S>,
+{
+    fn serialize(&self, serializer: &mut S) -> Result<Self::Resolver, S::Error> {
+        let () = Self::_SERIALIZE_ALLOWED;
+        rkyv::string::ArchivedString::serialize_from_str(self.as_str(), serializer)
+    }
+}
+
+#[cfg(feature = "rkyv")]
+impl<D, const MIN: usize, const MAX: usize, const MAX_BYTES: usize, L: LengthPolicy, F: FormatPolicy, const Z: bool, C: EqPolicy>
+    rkyv::Deserialize<BoundedStr<MIN, MAX, MAX_BYTES, L, F, Z, C>, D> for ArchivedBoundedStr<MIN, MAX, MAX_BYTES, L, F, Z, C>
+where
+    D: rkyv::rancor::Fallible + ?Sized,
+    D::Error: rkyv::rancor::Source,
+{
+    fn deserialize(&self, _: &mut D) -> Result<BoundedStr<MIN, MAX, MAX_BYTES, L, F, Z, C>, D::Error> {
+        use rkyv::rancor::Source;
+
+        BoundedStr::new(self.as_str()).map_err(D::Error::new)
+    }
+}
+
+// Adapters for `#[serde(with = "...")]` that coerce input instead of rejecting it.
+#[cfg(feature = "serde")]
+pub mod serde {
+    use super::{BoundedStr, BoundedStrError, DeError, EqPolicy, FormatPolicy, LengthPolicy, utf8_string, wipe};
+    use alloc::{string::String, vec::Vec};
+    use core::{fmt, marker::PhantomData};
+    use ::serde::{Deserializer, Serializer, de};
+
+    #[derive(Clone, Copy)]
+    enum Coerce {
+        Truncate,
+        Trimmed,
+        Lossy,
+    }
+
+    struct CoerceVisitor<const MIN: usize, const MAX: usize, const MAX_BYTES: usize, L, F, const Z: bool, C>(
+        Coerce,
+        PhantomData<(L, F, C)>,
+    );
+
+    impl<const MIN: usize, const MAX: usize, const MAX_BYTES: usize, L: LengthPolicy, F: FormatPolicy, const Z: bool, C: EqPolicy>
+        CoerceVisitor<MIN, MAX, MAX_BYTES, L, F, Z, C>
+    {
+        fn finish<E: de::Error>(
+            res: Result<BoundedStr<MIN, MAX, MAX_BYTES, L, F, Z, C>, BoundedStrError>,
+        ) -> Result<BoundedStr<MIN, MAX, MAX_BYTES, L, F, Z, C>, E> {
+            res.map_err(|e| E::custom(DeError::<L, F>(e, PhantomData)))
+        }
+    }
+
+    impl<'de, const MIN: usize, const MAX: usize, const MAX_BYTES: usize, L: LengthPolicy, F: FormatPolicy, const Z: bool, C: EqPolicy>
+        de::Visitor<'de> for CoerceVisitor<MIN, MAX, MAX_BYTES, L, F, Z, C>
+    {
+        type Value = BoundedStr<MIN, MAX, MAX_BYTES, L, F, Z, C>;
+
+        fn expecting(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+            match self.0 {
+                Coerce::Lossy => f.write_str("a string or byte buffer"),
+                _ => f.write_str("a string"),
+            }
+        }
+
+        fn visit_str<E: de::Error>(self, v: &str) -> Result<Self::Value, E> {
+            Self::finish(match self.0 {
+                Coerce::Truncate => BoundedStr::new_truncate(v),
+                Coerce::Trimmed => BoundedStr::new_trimmed(v),
+                Coerce::Lossy => BoundedStr::new(v),
+            })
+        }
+
+        fn visit_string<E: de::Error>(self, mut v: String) -> Result<Self::Value, E> {
+            let res = self.visit_str(&v);
+            // Zero bytes keep the buffer valid UTF-8.
+            wipe::<Z>(unsafe { v.as_bytes_mut() });
+            res
+        }
+
+        fn visit_bytes<E: de::Error>(self, v: &[u8]) -> Result<Self::Value, E> {
+            Self::finish(match self.0 {
+                Coerce::Lossy => BoundedStr::from_utf8_lossy(v),
+                _ => BoundedStr::from_utf8(v),
+            })
+        }
+    }
+
+    pub mod truncate {
+        use super::*;
+
+        pub fn serialize<S, const MIN: usize, const MAX: usize, const MAX_BYTES: usize, L: LengthPolicy, F: FormatPolicy, const Z: bool, C: EqPolicy>(
+            value: &BoundedStr<MIN, MAX, MAX_BYTES, L, F, Z, C>,
+            serializer: S,
+        ) -> Result<S::Ok, S::Error>
+        where
+            S: Serializer,
+        {
+            let () = BoundedStr::<MIN, MAX, MAX_BYTES, L, F, Z, C>::_SERIALIZE_ALLOWED;
+            serializer.serialize_str(value.as_str())
+        }
+
+        pub fn deserialize<'de, D, const MIN: usize, const MAX: usize, const MAX_BYTES: usize, L: LengthPolicy, F: FormatPolicy, const Z: bool, C: EqPolicy>(
+            deserializer: D,
+        ) -> Result<BoundedStr<MIN, MAX, MAX_BYTES, L, F, Z, C>, D::Error>
+        where
+            D: Deserializer<'de>,
+        {
+            deserializer.deserialize_string(CoerceVisitor(Coerce::Truncate, PhantomData))
+        }
+    }
+
+    pub mod trimmed {
+        use super::*;
+
+        pub fn serialize<S, const MIN: usize, const MAX: usize, const MAX_BYTES: usize, L: LengthPolicy, F: FormatPolicy, const Z: bool, C: EqPolicy>(
+            value: &BoundedStr<MIN, MAX, MAX_BYTES, L, F, Z, C>,
+            serializer: S,
+        ) -> Result<S::Ok, S::Error>
+        where
+            S: Serializer,
+        {
+            let () = BoundedStr::<MIN, MAX, MAX_BYTES, L, F, Z, C>::_SERIALIZE_ALLOWED;
+            serializer.serialize_str(value.as_str())
+        }
+
+        pub fn deserialize<'de, D, const MIN: usize, const MAX: usize, const MAX_BYTES: usize, L: LengthPolicy, F: FormatPolicy, const Z: bool, C: EqPolicy>(
+            deserializer: D,
+        ) -> Result<BoundedStr<MIN, MAX, MAX_BYTES, L, F, Z, C>, D::Error>
+        where
+            D: Deserializer<'de>,
+        {
+            deserializer.deserialize_string(CoerceVisitor(Coerce::Trimmed, PhantomData))
+        }
+    }
+
+    pub mod lossy {
+        use super::*;
+
+        pub fn serialize<S, const MIN: usize, const MAX: usize, const MAX_BYTES: usize, L: LengthPolicy, F: FormatPolicy, const Z: bool, C: EqPolicy>(
+            value: &BoundedStr<MIN, MAX, MAX_BYTES, L, F, Z, C>,
+            serializer: S,
+        ) -> Result<S::Ok, S::Error>
+        where
+            S: Serializer,
+        {
+            let () = BoundedStr::<MIN, MAX, MAX_BYTES, L, F, Z, C>::_SERIALIZE_ALLOWED;
+            serializer.serialize_str(value.as_str())
+        }
+
+        pub fn deserialize<'de, D, const MIN: usize, const MAX: usize, const MAX_BYTES: usize, L: LengthPolicy, F: FormatPolicy, const Z: bool, C: EqPolicy>(
+            deserializer: D,
+        ) -> Result<BoundedStr<MIN, MAX, MAX_BYTES, L, F, Z, C>, D::Error>
+        where
+            D: Deserializer<'de>,
+        {
+            deserializer.deserialize_byte_buf(CoerceVisitor(Coerce::Lossy, PhantomData))
+        }
+    }
+    // Length-prefixed bytes instead of a str, for binary formats.
+    pub mod bytes {
+        use super::*;
+
+        struct BytesVisitor<const MIN: usize, const MAX: usize, const MAX_BYTES: usize, L, F, const Z: bool, C>(
+            PhantomData<(L, F, C)>,
+        );
+
+        impl<'de, const MIN: usize, const MAX: usize, const MAX_BYTES: usize, L: LengthPolicy, F: FormatPolicy, const Z: bool, C: EqPolicy>
+            de::Visitor<'de> for BytesVisitor<MIN, MAX, MAX_BYTES, L, F, Z, C>
+        {
+            type Value = BoundedStr<MIN, MAX, MAX_BYTES, L, F, Z, C>;
+
+            fn expecting(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+                f.write_str("a utf-8 byte buffer")
+            }
+
+            fn visit_bytes<E: de::Error>(self, v: &[u8]) -> Result<Self::Value, E> {
+                CoerceVisitor::<MIN, MAX, MAX_BYTES, L, F, Z, C>::finish(BoundedStr::from_utf8(v))
+            }
+
+            fn visit_byte_buf<E: de::Error>(self, v: Vec<u8>) -> Result<Self::Value, E> {
+                match utf8_string(v) {
+                    Ok(s) => CoerceVisitor::<MIN, MAX, MAX_BYTES, L, F, Z, C>::finish(BoundedStr::try_from(s)),
+                    Err(mut v) => {
+                        wipe::<Z>(&mut v);
+                        Err(E::custom(BoundedStrError::InvalidUtf8))
+                    }
+                }
+            }
+
+            fn visit_str<E: de::Error>(self, v: &str) -> Result<Self::Value, E> {
+                CoerceVisitor::<MIN, MAX, MAX_BYTES, L, F, Z, C>::finish(BoundedStr::new(v))
+            }
+        }
+
+        pub fn serialize<S, const MIN: usize, const MAX: usize, const MAX_BYTES: usize, L: LengthPolicy, F: FormatPolicy, const Z: bool, C: EqPolicy>(
+            value: &BoundedStr<MIN, MAX, MAX_BYTES, L, F, Z, C>,
+            serializer: S,
+        ) -> Result<S::Ok, S::Error>
+        where
+            S: Serializer,
+        {
+            let () = BoundedStr::<MIN, MAX, MAX_BYTES, L, F, Z, C>::_SERIALIZE_ALLOWED;
+            serializer.serialize_bytes(value.as_bytes())
+        }
+
+        pub fn deserialize<'de, D, const MIN: usize, const MAX: usize, const MAX_BYTES: usize, L: LengthPolicy, F: FormatPolicy, const Z: bool, C: EqPolicy>(
+            deserializer: D,
+        ) -> Result<BoundedStr<MIN, MAX, MAX_BYTES, L, F, Z, C>, D::Error>
+        where
+            D: Deserializer<'de>,
+        {
+            deserializer.deserialize_byte_buf(BytesVisitor(PhantomData))
+        }
+    }
+
+    // Fixed-size frame: a 4-byte little-endian length followed by `MAX_BYTES`
+    // zero-padded bytes. Heap-stored values do not fit and fail to serialize.
+    pub mod padded {
+        use super::*;
+        use ::serde::ser::{self, SerializeTuple};
+
+        struct PaddedVisitor<const MIN: usize, const MAX: usize, const MAX_BYTES: usize, L, F, const Z: bool, C>(
+            PhantomData<(L, F, C)>,
+        );
+
+        impl<'de, const MIN: usize, const MAX: usize, const MAX_BYTES: usize, L: LengthPolicy, F: FormatPolicy, const Z: bool, C: EqPolicy>
+            de::Visitor<'de> for PaddedVisitor<MIN, MAX, MAX_BYTES, L, F, Z, C>
+        {
+            type Value = BoundedStr<MIN, MAX, MAX_BYTES, L, F, Z, C>;
+
+            fn expecting(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+                write!(f, "a {}-byte padded frame", 4 + MAX_BYTES)
+            }
+
+            fn visit_seq<A: de::SeqAccess<'de>>(self, mut seq: A) -> Result<Self::Value, A::Error> {
+                let mut prefix = [0u8; 4];
+                for (i, b) in prefix.iter_mut().enumerate() {
+                    *b = seq.next_element()?.ok_or_else(|| de::Error::invalid_length(i, &self))?;
+                }
+                let len = u32::from_le_bytes(prefix) as usize;
+
+                let mut buf = [0u8; MAX_BYTES];
+                let mut res = Ok(());
+                for (i, slot) in buf.iter_mut().enumerate() {
+                    match seq.next_element() {
+                        Ok(Some(b)) => *slot = b,
+                        Ok(None) => { res = Err(de::Error::invalid_length(4 + i, &self)); break; }
+                        Err(e) => { res = Err(e); break; }
+                    }
+                }
+
+                let res = res.and_then(|()| {
+                    if len > MAX_BYTES {
+                        return Err(de::Error::custom(BoundedStrError::TooManyBytes { actual: len, max_bytes: MAX_BYTES }));
+                    }
+                    CoerceVisitor::<MIN, MAX, MAX_BYTES, L, F, Z, C>::finish(BoundedStr::from_utf8(&buf[..len]))
+                });
+                wipe::<Z>(&mut buf);
+                res
+            }
+        }
+
+        pub fn serialize<S, const MIN: usize, const MAX: usize, const MAX_BYTES: usize, L: LengthPolicy, F: FormatPolicy, const Z: bool, C: EqPolicy>(
+            value: &BoundedStr<MIN, MAX, MAX_BYTES, L, F, Z, C>,
+            serializer: S,
+        ) -> Result<S::Ok, S::Error>
+        where
+            S: Serializer,
+        {
+            let () = BoundedStr::<MIN, MAX, MAX_BYTES, L, F, Z, C>::_SERIALIZE_ALLOWED;
+            let bytes = value.as_bytes();
+            if bytes.len() > MAX_BYTES {
+                return Err(ser::Error::custom(BoundedStrError::TooManyBytes { actual: bytes.len(), max_bytes: MAX_BYTES }));
+            }
+
+            let mut tuple = serializer.serialize_tuple(4 + MAX_BYTES)?;
+            for b in (bytes.len() as u32).to_le_bytes() {
+                tuple.serialize_element(&b)?;
+            }
+            for i in 0..MAX_BYTES {
+                tuple.serialize_element(&bytes.get(i).copied().unwrap_or(0))?;
+            }
+            tuple.end()
+        }
+
+        pub fn deserialize<'de, D, const MIN: usize, const MAX: usize, const MAX_BYTES: usize, L: LengthPolicy, F: FormatPolicy, const Z: bool, C: EqPolicy>(
+            deserializer: D,
+        ) -> Result<BoundedStr<MIN, MAX, MAX_BYTES, L, F, Z, C>, D::Error>
+        where
+            D: Deserializer<'de>,
+        {
+            deserializer.deserialize_tuple(4 + MAX_BYTES, PaddedVisitor(PhantomData))
+        }
+    }
+}
+
+
+#[macro_export]
+macro_rules! bounded_format {
+    ($ty:ty, $($arg:tt)*) => {
+        <$ty>::try_from_fmt(::core::format_args!($($arg)*))
+    };
+}
+
+#[macro_export]
+macro_rules! bounded_str {
+    ($ty:ty, $s:expr) => {
+        const { <$ty>::new_const($s) }
+    };
+}
+
+// Declares a `RegexPattern` marker for `RegexPolicy`:
+// `regex_policy!(pub Sku = r"[A-Z]{3}-[0-9]{4}");`. The pattern is anchored and
+// compiled on first use; an invalid one panics there.
+#[cfg(feature = "regex-lite")]
+#[macro_export]
+macro_rules! regex_policy {
+    ($(#[$attr:meta])* $vis:vis $name:ident = $pattern:literal $(;)?) => {
+        $(#[$attr])*
+        #[derive(Clone, Copy, Debug, PartialEq, Eq, Default)]
+        $vis struct $name;
+        impl $crate::RegexPattern for $name {
+            const PATTERN: &'static str = ::core::concat!("^(?:", $pattern, ")$");
+
+            fn regex() -> &'static $crate::__regex::Regex {
+                static REGEX: $crate::__regex::OnceLock<$crate::__regex::Regex> = $crate::__regex::OnceLock::new();
+                REGEX.get_or_init(|| $crate::__regex::Regex::new(Self::PATTERN).expect("invalid regex_policy! pattern"))
+            }
+        }
+    };
+}
+
+#[cfg(feature = "regex-lite")]
+#[doc(hidden)]
+pub mod __regex {
+    pub use regex_lite::Regex;
+    pub use std::sync::OnceLock;
+}
+
+pub type StackStr<const MIN: usize, const MAX: usize, const MAXB: usize = MAX, L = Bytes, F = AllowAll, const Z: bool = false, C = FastEq> = BoundedStr<MIN, MAX, MAXB, L, F, Z, C>;
+
+// One-stop type for passwords and API keys: zeroized on drop, constant-time
+// `==`, redacted `Debug`/`Display`, and only serializable with `serialize-secrets`.
+pub type SecretBoundedStr<const MIN: usize, const MAX: usize, const MAXB: usize = MAX, L = Bytes, F = AllowAll> = BoundedStr<MIN, MAX, MAXB, L, F, true, ConstantTime>;
+
+#[cfg(feature = "alloc")]
+pub type FlexStr<const MIN: usize, const MAX: usize, const MAXB: usize = 4096, L = Bytes, F = AllowAll, const Z: bool = false, C = FastEq> = BoundedStr<MIN, MAX, MAXB, L, F, Z, C>;
+
+// No inline buffer at all: the enum holds just the `Vec`, so a 64 KB body costs
+// a few words in a struct instead of 64 KB of stack. Only empty values are inline.
+#[cfg(feature = "alloc")]
+pub type HeapStr<const MIN: usize, const MAX: usize, L = Bytes, F = AllowAll, const Z: bool = false, C = FastEq> = BoundedStr<MIN, MAX, 0, L, F, Z, C>;
+
+// Borrowed counterpart of `BoundedStr`: validates a `&'a str` in place, for
+// parsers that only need to check bounds before routing data.
+pub struct BoundedStrRef<'a, const MIN: usize, const MAX: usize, L: LengthPolicy = Bytes, F: FormatPolicy = AllowAll> {
+    s: &'a str,
+    logical_len: usize,
+    _marker: PhantomData<(L, F)>,
+}
+
+unsafe impl<'a, const MIN: usize, const MAX: usize, L: LengthPolicy, F: FormatPolicy> Send for BoundedStrRef<'a, MIN, MAX, L, F> {}
+unsafe impl<'a, const MIN: usize, const MAX: usize, L: LengthPolicy, F: FormatPolicy> Sync for BoundedStrRef<'a, MIN, MAX, L, F> {}
+impl<'a, const MIN: usize, const MAX: usize, L: LengthPolicy, F: FormatPolicy> UnwindSafe for BoundedStrRef<'a, MIN, MAX, L, F> {}
+impl<'a, const MIN: usize, const MAX: usize, L: LengthPolicy, F: FormatPolicy> RefUnwindSafe for BoundedStrRef<'a, MIN, MAX, L, F> {}
+
+impl<'a, const MIN: usize, const MAX: usize, L: LengthPolicy, F: FormatPolicy> BoundedStrRef<'a, MIN, MAX, L, F> {
+    pub fn new(s: &'a str) -> Result<Self, BoundedStrError> {
+        const { assert!(MIN <= MAX, "MIN must be <= MAX") };
+        let logical_len = validate_bounds::<MIN, MAX, L, F>(s)?;
+        Ok(Self { s, logical_len, _marker: PhantomData })
+    }
+
+    // Zero-copy counterparts of `BoundedStr::split_bounded()`/`lines_bounded()`.
+    pub fn split_bounded<'p>(input: &'a str, sep: &'p str) -> impl Iterator<Item = Result<Self, BoundedStrError>> {
+        input.split(sep).map(Self::new)
+    }
+
+    pub fn lines_bounded(input: &'a str) -> impl Iterator<Item = Result<Self, BoundedStrError>> {
+        input.lines().map(Self::new)
+    }
+
+    #[inline(always)]
+    pub fn as_str(&self) -> &'a str {
+        self.s
+    }
+
+    #[inline(always)]
+    pub fn len_logical(&self) -> usize {
+        self.logical_len
+    }
+
+    // Copies into an owned value without re-running the checks. Fails only
+    // without `alloc`, when the content doesn't fit `MAX_BYTES`.
+    pub fn to_bounded<const MAX_BYTES: usize>(&self) -> Result<BoundedStr<MIN, MAX, MAX_BYTES, L, F>, BoundedStrError> {
+        let () = BoundedStr::<MIN, MAX, MAX_BYTES, L, F>::_CHECK;
+        let mut storage = Storage::empty();
+        storage.push::<false>(self.s.as_bytes())?;
+        Ok(BoundedStr::from_parts(storage, self.logical_len))
+    }
+}
+
+impl<const MIN: usize, const MAX: usize, const MAX_BYTES: usize, L: LengthPolicy, F: FormatPolicy, const Z: bool, C: EqPolicy>
+    BoundedStr<MIN, MAX, MAX_BYTES, L, F, Z, C>
+{
+    #[inline(always)]
+    pub fn as_bounded_ref(&self) -> BoundedStrRef<'_, MIN, MAX, L, F> {
+        BoundedStrRef { s: self.as_str(), logical_len: self.len_logical(), _marker: PhantomData }
+    }
+}
+
+impl<'a, const MIN: usize, const MAX: usize, L: LengthPolicy, F: FormatPolicy> TryFrom<&'a str> for BoundedStrRef<'a, MIN, MAX, L, F> {
+    type Error = BoundedStrError;
+    fn try_from(s: &'a str) -> Result<Self, Self::Error> { Self::new(s) }
+}
+
+impl<const MIN: usize, const MAX: usize, L: LengthPolicy, F: FormatPolicy> Clone for BoundedStrRef<'_, MIN, MAX, L, F> {
+    fn clone(&self) -> Self { *self }
+}
+
+impl<const MIN: usize, const MAX: usize, L: LengthPolicy, F: FormatPolicy> Copy for BoundedStrRef<'_, MIN, MAX, L, F> {}
+
+impl<const MIN: usize, const MAX: usize, L: LengthPolicy, F: FormatPolicy> Deref for BoundedStrRef<'_, MIN, MAX, L, F> {
+    type Target = str;
+    fn deref(&self) -> &str { self.s }
+}
+
+impl<const MIN: usize, const MAX: usize, L: LengthPolicy, F: FormatPolicy> AsRef<str> for BoundedStrRef<'_, MIN, MAX, L, F> {
+    fn as_ref(&self) -> &str { self.s }
+}
+
+impl<const MIN: usize, const MAX: usize, L: LengthPolicy, F: FormatPolicy> PartialEq for BoundedStrRef<'_, MIN, MAX, L, F> {
+    fn eq(&self, other: &Self) -> bool { self.s == other.s }
+}
+
+impl<const MIN: usize, const MAX: usize, L: LengthPolicy, F: FormatPolicy> Eq for BoundedStrRef<'_, MIN, MAX, L, F> {}
+
+impl<const MIN: usize, const MAX: usize, L: LengthPolicy, F: FormatPolicy> PartialEq<&str> for BoundedStrRef<'_, MIN, MAX, L, F> {
+    fn eq(&self, other: &&str) -> bool { self.s == *other }
+}
+
+impl<const MIN: usize, const MAX: usize, L: LengthPolicy, F: FormatPolicy> Hash for BoundedStrRef<'_, MIN, MAX, L, F> {
+    fn hash<H: Hasher>(&self, state: &mut H) { self.s.hash(state) }
+}
+
+impl<const MIN: usize, const MAX: usize, L: LengthPolicy, F: FormatPolicy> Display for BoundedStrRef<'_, MIN, MAX, L, F> {
+    fn fmt(&self, f: &mut Formatter<'_>) -> fmt::Result { f.pad(self.s) }
+}
+
+impl<const MIN: usize, const MAX: usize, L: LengthPolicy, F: FormatPolicy> fmt::Debug for BoundedStrRef<'_, MIN, MAX, L, F> {
+    fn fmt(&self, f: &mut Formatter<'_>) -> fmt::Result {
+        f.debug_struct("BoundedStrRef")
+            .field("value", &self.s)
+            .field("len_logical", &self.logical_len)
+            .finish()
+    }
+}
+
+// Validated once at construction; the borrowed content is only copied on the
+// first edit or `into_owned()`, for inputs that mostly pass through unchanged.
+pub enum BoundedCow<'a, const MIN: usize, const MAX: usize, const MAX_BYTES: usize, L: LengthPolicy = Bytes, F: FormatPolicy = AllowAll> {
+    Borrowed(BoundedStrRef<'a, MIN, MAX, L, F>),
+    Owned(BoundedStr<MIN, MAX, MAX_BYTES, L, F>),
+}
+
+impl<'a, const MIN: usize, const MAX: usize, const MAX_BYTES: usize, L: LengthPolicy, F: FormatPolicy>
+    BoundedCow<'a, MIN, MAX, MAX_BYTES, L, F>
+{
+    pub fn new(s: &'a str) -> Result<Self, BoundedStrError> {
+        BoundedStrRef::new(s).map(Self::Borrowed)
+    }
+
+    #[inline(always)]
+    pub fn as_str(&self) -> &str {
+        match self {
+            Self::Borrowed(r) => r.as_str(),
+            Self::Owned(o) => o.as_str(),
+        }
+    }
+
+    #[inline(always)]
+    pub fn len_logical(&self) -> usize {
+        match self {
+            Self::Borrowed(r) => r.len_logical(),
+            Self::Owned(o) => o.len_logical(),
+        }
+    }
+
+    #[inline(always)]
+    pub fn is_borrowed(&self) -> bool {
+        matches!(self, Self::Borrowed(_))
+    }
+
+    // Copies borrowed content into an owned value first. Fails only without
+    // `alloc`, when the content doesn't fit `MAX_BYTES`.
+    pub fn to_mut(&mut self) -> Result<&mut BoundedStr<MIN, MAX, MAX_BYTES, L, F>, BoundedStrError> {
+        if let Self::Borrowed(r) = self {
+            *self = Self::Owned(r.to_bounded()?);
+        }
+        match self {
+            Self::Owned(o) => Ok(o),
+            Self::Borrowed(_) => unreachable!(),
+        }
+    }
+
+    pub fn into_owned(self) -> Result<BoundedStr<MIN, MAX, MAX_BYTES, L, F>, BoundedStrError> {
+        match self {
+            Self::Borrowed(r) => r.to_bounded(),
+            Self::Owned(o) => Ok(o),
+        }
+    }
+
+    pub fn mutate<Mut, R>(&mut self, mutator: Mut) -> Result<R, BoundedStrError>
+    where
+        Mut: FnOnce(&mut [u8], &mut usize) -> R,
+    {
+        self.to_mut()?.mutate(mutator)
+    }
+}
+
+impl<'a, const MIN: usize, const MAX: usize, const MAX_BYTES: usize, L: LengthPolicy, F: FormatPolicy>
+    From<BoundedStrRef<'a, MIN, MAX, L, F>> for BoundedCow<'a, MIN, MAX, MAX_BYTES, L, F>
+{
+    fn from(r: BoundedStrRef<'a, MIN, MAX, L, F>) -> Self { Self::Borrowed(r) }
+}
+
+impl<const MIN: usize, const MAX: usize, const MAX_BYTES: usize, L: LengthPolicy, F: FormatPolicy>
+    From<BoundedStr<MIN, MAX, MAX_BYTES, L, F>> for BoundedCow<'_, MIN, MAX, MAX_BYTES, L, F>
+{
+    fn from(o: BoundedStr<MIN, MAX, MAX_BYTES, L, F>) -> Self { Self::Owned(o) }
+}
+
+impl<const MIN: usize, const MAX: usize, const MAX_BYTES: usize, L: LengthPolicy, F: FormatPolicy>
+    Clone for BoundedCow<'_, MIN, MAX, MAX_BYTES, L, F>
+{
+    fn clone(&self) -> Self {
+        match self {
+            Self::Borrowed(r) => Self::Borrowed(*r),
+            Self::Owned(o) => Self::Owned(o.clone()),
+        }
+    }
+}
+
+impl<const MIN: usize, const MAX: usize, const MAX_BYTES: usize, L: LengthPolicy, F: FormatPolicy>
+    Deref for BoundedCow<'_, MIN, MAX, MAX_BYTES, L, F>
+{
+    type Target = str;
+    fn deref(&self) -> &str { self.as_str() }
+}
+
+impl<const MIN: usize, const MAX: usize, const MAX_BYTES: usize, L: LengthPolicy, F: FormatPolicy>
+    AsRef<str> for BoundedCow<'_, MIN, MAX, MAX_BYTES, L, F>
+{
+    fn as_ref(&self) -> &str { self.as_str() }
+}
+
+impl<const MIN: usize, const MAX: usize, const MAX_BYTES: usize, L: LengthPolicy, F: FormatPolicy>
+    PartialEq for BoundedCow<'_, MIN, MAX, MAX_BYTES, L, F>
+{
+    fn eq(&self, other: &Self) -> bool { self.as_str() == other.as_str() }
+}
+
+impl<const MIN: usize, const MAX: usize, const MAX_BYTES: usize, L: LengthPolicy, F: FormatPolicy>
+    Eq for BoundedCow<'_, MIN, MAX, MAX_BYTES, L, F> {}
+
+impl<const MIN: usize, const MAX: usize, const MAX_BYTES: usize, L: LengthPolicy, F: FormatPolicy>
+    PartialEq<&str> for BoundedCow<'_, MIN, MAX, MAX_BYTES, L, F>
+{
+    fn eq(&self, other: &&str) -> bool { self.as_str() == *other }
+}
+
+impl<const MIN: usize, const MAX: usize, const MAX_BYTES: usize, L: LengthPolicy, F: FormatPolicy>
+    Hash for BoundedCow<'_, MIN, MAX, MAX_BYTES, L, F>
+{
+    fn hash<H: Hasher>(&self, state: &mut H) { self.as_str().hash(state) }
+}
+
+impl<const MIN: usize, const MAX: usize, const MAX_BYTES: usize, L: LengthPolicy, F: FormatPolicy>
+    Display for BoundedCow<'_, MIN, MAX, MAX_BYTES, L, F>
+{
+    fn fmt(&self, f: &mut Formatter<'_>) -> fmt::Result { f.pad(self.as_str()) }
+}
+
+impl<const MIN: usize, const MAX: usize, const MAX_BYTES: usize, L: LengthPolicy, F: FormatPolicy>
+    fmt::Debug for BoundedCow<'_, MIN, MAX, MAX_BYTES, L, F>
+{
+    fn fmt(&self, f: &mut Formatter<'_>) -> fmt::Result {
+        match self {
+            Self::Borrowed(r) => f.debug_tuple("Borrowed").field(r).finish(),
+            Self::Owned(o) => f.debug_tuple("Owned").field(o).finish(),
+        }
+    }
+}
+
+// Stack-only sibling of `StackStr` that is `Copy`: no `Drop`, no `Z` and no
+// heap variant, for small IDs and labels kept in large tables. The logical
+// length isn't cached, so values stay as small as the buffer allows.
+pub struct CopyStr<const MIN: usize, const MAX: usize, const MAX_BYTES: usize, L: LengthPolicy = Bytes, F: FormatPolicy = AllowAll> {
+    buf: [MaybeUninit<u8>; MAX_BYTES],
+    len: InlineLen,
+    _marker: PhantomData<(L, F)>,
+}
+
+unsafe impl<const MIN: usize, const MAX: usize, const MAX_BYTES: usize, L: LengthPolicy, F: FormatPolicy> Send for CopyStr<MIN, MAX, MAX_BYTES, L, F> {}
+unsafe impl<const MIN: usize, const MAX: usize, const MAX_BYTES: usize, L: LengthPolicy, F: FormatPolicy> Sync for CopyStr<MIN, MAX, MAX_BYTES, L, F> {}
+impl<const MIN: usize, const MAX: usize, const MAX_BYTES: usize, L: LengthPolicy, F: FormatPolicy> UnwindSafe for CopyStr<MIN, MAX, MAX_BYTES, L, F> {}
+impl<const MIN: usize, const MAX: usize, const MAX_BYTES: usize, L: LengthPolicy, F: FormatPolicy> RefUnwindSafe for CopyStr<MIN, MAX, MAX_BYTES, L, F> {}
+
+impl<const MIN: usize, const MAX: usize, const MAX_BYTES: usize, L: LengthPolicy, F: FormatPolicy> CopyStr<MIN, MAX, MAX_BYTES, L, F> {
+    pub const MIN_LEN: usize = MIN;
+    pub const MAX_LEN: usize = MAX;
+    pub const MAX_INLINE_BYTES: usize = MAX_BYTES;
+
+    pub fn new(s: &str) -> Result<Self, BoundedStrError> {
+        const {
+            assert!(MIN <= MAX, "MIN must be <= MAX");
+        }
+        validate_bounds::<MIN, MAX, L, F>(s)?;
+        if s.len() > MAX_BYTES {
+            return Err(BoundedStrError::TooManyBytes { actual: s.len(), max_bytes: MAX_BYTES });
+        }
+        let mut buf = [MaybeUninit::uninit(); MAX_BYTES];
+        init_prefix(&mut buf, s.as_bytes());
+        let len = InlineLen::new(s.len(), &mut buf);
+        Ok(Self { buf, len, _marker: PhantomData })
+    }
+
+    #[inline(always)]
+    pub fn as_str(&self) -> &str {
+        unsafe { str::from_utf8_unchecked(assume_init(&self.buf[..self.len.get(&self.buf)])) }
+    }
+
+    #[inline(always)]
+    pub fn len_bytes(&self) -> usize {
+        self.len.get(&self.buf)
+    }
+
+    // Measured on each call; O(1) only for `Bytes`.
+    #[inline(always)]
+    pub fn len_logical(&self) -> usize {
+        L::logical_len(self.as_str())
+    }
+
+    pub fn to_bounded<const Z: bool, C: EqPolicy>(&self) -> BoundedStr<MIN, MAX, MAX_BYTES, L, F, Z, C> {
+        let () = BoundedStr::<MIN, MAX, MAX_BYTES, L, F, Z, C>::_CHECK;
+        BoundedStr { storage: Storage::stack(self.as_str().as_bytes()), _marker: PhantomData }
+    }
+}
+
+impl<const MIN: usize, const MAX: usize, const MAX_BYTES: usize, L: LengthPolicy, F: FormatPolicy> TryFrom<&str> for CopyStr<MIN, MAX, MAX_BYTES, L, F> {
+    type Error = BoundedStrError;
+    fn try_from(s: &str) -> Result<Self, Self::Error> { Self::new(s) }
+}
+
+// Fails with `TooManyBytes` only for heap values that don't fit the buffer.
+// Zeroizing values are excluded: copies of a secret would never be wiped.
+impl<const MIN: usize, const MAX: usize, const MAX_BYTES: usize, L: LengthPolicy, F: FormatPolicy, C: EqPolicy> TryFrom<&BoundedStr<MIN, MAX, MAX_BYTES, L, F, false, C>> for CopyStr<MIN, MAX, MAX_BYTES, L, F> {
+    type Error = BoundedStrError;
+    fn try_from(s: &BoundedStr<MIN, MAX, MAX_BYTES, L, F, false, C>) -> Result<Self, Self::Error> {
+        let bytes = s.as_bytes();
+        if bytes.len() > MAX_BYTES {
+            return Err(BoundedStrError::TooManyBytes { actual: bytes.len(), max_bytes: MAX_BYTES });
+        }
+        let mut buf = [MaybeUninit::uninit(); MAX_BYTES];
+        init_prefix(&mut buf, bytes);
+        let len = InlineLen::new(bytes.len(), &mut buf);
+        Ok(Self { buf, len, _marker: PhantomData })
+    }
+}
+
+impl<const MIN: usize, const MAX: usize, const MAX_BYTES: usize, L: LengthPolicy, F: FormatPolicy, const Z: bool, C: EqPolicy> From<CopyStr<MIN, MAX, MAX_BYTES, L, F>> for BoundedStr<MIN, MAX, MAX_BYTES, L, F, Z, C> {
+    fn from(s: CopyStr<MIN, MAX, MAX_BYTES, L, F>) -> Self { s.to_bounded() }
+}
+
+impl<const MIN: usize, const MAX: usize, const MAX_BYTES: usize, L: LengthPolicy, F: FormatPolicy> Clone for CopyStr<MIN, MAX, MAX_BYTES, L, F> {
+    fn clone(&self) -> Self { *self }
+}
+
+impl<const MIN: usize, const MAX: usize, const MAX_BYTES: usize, L: LengthPolicy, F: FormatPolicy> Copy for CopyStr<MIN, MAX, MAX_BYTES, L, F> {}
+
+impl<const MIN: usize, const MAX: usize, const MAX_BYTES: usize, L: LengthPolicy, F: FormatPolicy> Deref for CopyStr<MIN, MAX, MAX_BYTES, L, F> {
+    type Target = str;
+    fn deref(&self) -> &str { self.as_str() }
+}
+
+impl<const MIN: usize, const MAX: usize, const MAX_BYTES: usize, L: LengthPolicy, F: FormatPolicy> AsRef<str> for CopyStr<MIN, MAX, MAX_BYTES, L, F> {
+    fn as_ref(&self) -> &str { self.as_str() }
+}
+
+impl<const MIN: usize, const MAX: usize, const MAX_BYTES: usize, L: LengthPolicy, F: FormatPolicy> Borrow<str> for CopyStr<MIN, MAX, MAX_BYTES, L, F> {
+    fn borrow(&self) -> &str { self.as_str() }
+}
+
+impl<const MIN: usize, const MAX: usize, const MAX_BYTES: usize, L: LengthPolicy, F: FormatPolicy> PartialEq for CopyStr<MIN, MAX, MAX_BYTES, L, F> {
+    fn eq(&self, other: &Self) -> bool { self.as_str() == other.as_str() }
+}
+
+impl<const MIN: usize, const MAX: usize, const MAX_BYTES: usize, L: LengthPolicy, F: FormatPolicy> Eq for CopyStr<MIN, MAX, MAX_BYTES, L, F> {}
+
+impl<const MIN: usize, const MAX: usize, const MAX_BYTES: usize, L: LengthPolicy, F: FormatPolicy> PartialEq<&str> for CopyStr<MIN, MAX, MAX_BYTES, L, F> {
+    fn eq(&self, other: &&str) -> bool { self.as_str() == *other }
+}
+
+impl<const MIN: usize, const MAX: usize, const MAX_BYTES: usize, L: LengthPolicy, F: FormatPolicy> PartialOrd for CopyStr<MIN, MAX, MAX_BYTES, L, F> {
+    fn partial_cmp(&self, other: &Self) -> Option<Ordering> { Some(self.cmp(other)) }
+}
+
+impl<const MIN: usize, const MAX: usize, const MAX_BYTES: usize, L: LengthPolicy, F: FormatPolicy> Ord for CopyStr<MIN, MAX, MAX_BYTES, L, F> {
+    fn cmp(&self, other: &Self) -> Ordering { self.as_str().cmp(other.as_str()) }
+}
+
+impl<const MIN: usize, const MAX: usize, const MAX_BYTES: usize, L: LengthPolicy, F: FormatPolicy> Hash for CopyStr<MIN, MAX, MAX_BYTES, L, F> {
+    fn hash<H: Hasher>(&self, state: &mut H) { self.as_str().hash(state) }
+}
+
+impl<const MIN: usize, const MAX: usize, const MAX_BYTES: usize, L: LengthPolicy, F: FormatPolicy> Display for CopyStr<MIN, MAX, MAX_BYTES, L, F> {
+    fn fmt(&self, f: &mut Formatter<'_>) -> fmt::Result { f.pad(self.as_str()) }
+}
+
+impl<const MIN: usize, const MAX: usize, const MAX_BYTES: usize, L: LengthPolicy, F: FormatPolicy> fmt::Debug for CopyStr<MIN, MAX, MAX_BYTES, L, F> {
+    fn fmt(&self, f: &mut Formatter<'_>) -> fmt::Result {
+        f.debug_struct("CopyStr")
+            .field("value", &self.as_str())
+            .field("len_bytes", &self.len_bytes())
+            .finish()
+    }
+}
+
+// Keeps a NUL after the content, so `as_c_str()`/`as_ptr()` hand it to C APIs
+// without building a `CString`. Interior NULs are rejected whatever `F` is, and
+// the terminator counts toward `MAX_BYTES`.
+pub struct BoundedCStr<const MIN: usize, const MAX: usize, const MAX_BYTES: usize, L: LengthPolicy = Bytes, F: FormatPolicy = NoNul> {
+    // The content followed by one NUL byte.
+    storage: Storage<MAX_BYTES>,
+    logical_len: usize,
+    _marker: PhantomData<(L, F)>,
+}
+
+unsafe impl<const MIN: usize, const MAX: usize, const MAX_BYTES: usize, L: LengthPolicy, F: FormatPolicy> Send for BoundedCStr<MIN, MAX, MAX_BYTES, L, F> {}
+unsafe impl<const MIN: usize, const MAX: usize, const MAX_BYTES: usize, L: LengthPolicy, F: FormatPolicy> Sync for BoundedCStr<MIN, MAX, MAX_BYTES, L, F> {}
+impl<const MIN: usize, const MAX: usize, const MAX_BYTES: usize, L: LengthPolicy, F: FormatPolicy> UnwindSafe for BoundedCStr<MIN, MAX, MAX_BYTES, L, F> {}
+impl<const MIN: usize, const MAX: usize, const MAX_BYTES: usize, L: LengthPolicy, F: FormatPolicy> RefUnwindSafe for BoundedCStr<MIN, MAX, MAX_BYTES, L, F> {}
+
+impl<const MIN: usize, const MAX: usize, const MAX_BYTES: usize, L: LengthPolicy, F: FormatPolicy> BoundedCStr<MIN, MAX, MAX_BYTES, L, F> {
+    pub fn new(s: &str) -> Result<Self, BoundedStrError> {
+        const {
+            assert!(MIN <= MAX, "MIN must be <= MAX");
+        }
+        let logical_len = validate_bounds::<MIN, MAX, L, F>(s)?;
+        if !NoNul::check(s) { return Err(BoundedStrError::invalid_content::<NoNul>(s)); }
+        let mut storage = Storage::empty();
+        #[cfg(feature = "alloc")]
+        storage.reserve::<false>(s.len() + 1);
+        storage.push::<false>(s.as_bytes())?;
+        storage.push::<false>(&[0])?;
+        Ok(Self { storage, logical_len, _marker: PhantomData })
+    }
+
+    #[inline(always)]
+    pub fn as_str(&self) -> &str {
+        unsafe { str::from_utf8_unchecked(self.as_bytes()) }
+    }
+
+    #[inline(always)]
+    pub fn as_bytes(&self) -> &[u8] {
+        let bytes = self.storage.as_bytes();
+        &bytes[..bytes.len() - 1]
+    }
+
+    #[inline(always)]
+    pub fn as_bytes_with_nul(&self) -> &[u8] {
+        self.storage.as_bytes()
+    }
+
+    #[inline(always)]
+    pub fn as_c_str(&self) -> &CStr {
+        unsafe { CStr::from_bytes_with_nul_unchecked(self.storage.as_bytes()) }
+    }
+
+    // Valid for as long as `self` is neither moved nor dropped.
+    #[inline(always)]
+    pub fn as_ptr(&self) -> *const core::ffi::c_char {
+        self.storage.as_bytes().as_ptr().cast()
+    }
+
+    // Excludes the terminator.
+    #[inline(always)]
+    pub fn len_bytes(&self) -> usize {
+        self.storage.as_bytes().len() - 1
+    }
+
+    #[inline(always)]
+    pub fn len_logical(&self) -> usize {
+        self.logical_len
+    }
+
+    #[inline(always)]
+    pub fn is_heap(&self) -> bool {
+        self.storage.is_heap()
+    }
+
+    // Copies the content without the terminator or re-running the checks.
+    pub fn to_bounded<const MAX_BYTES2: usize>(&self) -> Result<BoundedStr<MIN, MAX, MAX_BYTES2, L, F>, BoundedStrError> {
+        let () = BoundedStr::<MIN, MAX, MAX_BYTES2, L, F>::_CHECK;
+        let mut storage = Storage::empty();
+        storage.push::<false>(self.as_bytes())?;
+        Ok(BoundedStr::from_parts(storage, self.logical_len))
+    }
+}
+
+impl<const MIN: usize, const MAX: usize, const MAX_BYTES: usize, L: LengthPolicy, F: FormatPolicy> TryFrom<&str> for BoundedCStr<MIN, MAX, MAX_BYTES, L, F> {
+    type Error = BoundedStrError;
+    fn try_from(s: &str) -> Result<Self, Self::Error> { Self::new(s) }
+}
+
+impl<const MIN: usize, const MAX: usize, const MAX_BYTES: usize, L: LengthPolicy, F: FormatPolicy> TryFrom<&CStr> for BoundedCStr<MIN, MAX, MAX_BYTES, L, F> {
+    type Error = BoundedStrError;
+    fn try_from(s: &CStr) -> Result<Self, Self::Error> {
+        Self::new(utf8_str(s.to_bytes()).ok_or(BoundedStrError::InvalidUtf8)?)
+    }
+}
+
+impl<const MIN: usize, const MAX: usize, const MAX_BYTES: usize, L: LengthPolicy, F: FormatPolicy> Clone for BoundedCStr<MIN, MAX, MAX_BYTES, L, F> {
+    fn clone(&self) -> Self {
+        Self { storage: self.storage.clone(), logical_len: self.logical_len, _marker: PhantomData }
+    }
+}
+
+impl<const MIN: usize, const MAX: usize, const MAX_BYTES: usize, L: LengthPolicy, F: FormatPolicy> Deref for BoundedCStr<MIN, MAX, MAX_BYTES, L, F> {
+    type Target = str;
+    fn deref(&self) -> &str { self.as_str() }
+}
+
+impl<const MIN: usize, const MAX: usize, const MAX_BYTES: usize, L: LengthPolicy, F: FormatPolicy> AsRef<str> for BoundedCStr<MIN, MAX, MAX_BYTES, L, F> {
+    fn as_ref(&self) -> &str { self.as_str() }
+}
+
+impl<const MIN: usize, const MAX: usize, const MAX_BYTES: usize, L: LengthPolicy, F: FormatPolicy> AsRef<CStr> for BoundedCStr<MIN, MAX, MAX_BYTES, L, F> {
+    fn as_ref(&self) -> &CStr { self.as_c_str() }
+}
+
+impl<const MIN: usize, const MAX: usize, const MAX_BYTES: usize, L: LengthPolicy, F: FormatPolicy> PartialEq for BoundedCStr<MIN, MAX, MAX_BYTES, L, F> {
+    fn eq(&self, other: &Self) -> bool { self.as_bytes() == other.as_bytes() }
+}
+
+impl<const MIN: usize, const MAX: usize, const MAX_BYTES: usize, L: LengthPolicy, F: FormatPolicy> Eq for BoundedCStr<MIN, MAX, MAX_BYTES, L, F> {}
+
+impl<const MIN: usize, const MAX: usize, const MAX_BYTES: usize, L: LengthPolicy, F: FormatPolicy> PartialEq<&str> for BoundedCStr<MIN, MAX, MAX_BYTES, L, F> {
+    fn eq(&self, other: &&str) -> bool { self.as_str() == *other }
+}
+
+impl<const MIN: usize, const MAX: usize, const MAX_BYTES: usize, L: LengthPolicy, F: FormatPolicy> Hash for BoundedCStr<MIN, MAX, MAX_BYTES, L, F> {
+    fn hash<H: Hasher>(&self, state: &mut H) { self.as_str().hash(state) }
+}
+
+impl<const MIN: usize, const MAX: usize, const MAX_BYTES: usize, L: LengthPolicy, F: FormatPolicy> Display for BoundedCStr<MIN, MAX, MAX_BYTES, L, F> {
+    fn fmt(&self, f: &mut Formatter<'_>) -> fmt::Result { f.pad(self.as_str()) }
+}
+
+impl<const MIN: usize, const MAX: usize, const MAX_BYTES: usize, L: LengthPolicy, F: FormatPolicy> fmt::Debug for BoundedCStr<MIN, MAX, MAX_BYTES, L, F> {
+    fn fmt(&self, f: &mut Formatter<'_>) -> fmt::Result {
+        f.debug_struct("BoundedCStr")
+            .field("value", &self.as_c_str())
+            .field("len_logical", &self.logical_len)
+            .finish()
+    }
+}
+
+// Read-mostly sibling of `FlexStr` for large bodies fanned out to many readers.
+#[cfg(all(feature = "alloc", target_has_atomic = "ptr"))]
+pub use shared::SharedBoundedStr;
+
+#[cfg(all(feature = "alloc", target_has_atomic = "ptr"))]
+mod shared {
+    use super::*;
+    use alloc::sync::Arc;
+
+    // Backed by `Arc<str>`: `clone()` bumps a refcount instead of copying the
+    // body, and `mutate()` copies on write. There is no `Z` parameter, since a
+    // shared buffer can't be wiped while other clones may still read it.
+    pub struct SharedBoundedStr<const MIN: usize, const MAX: usize, L: LengthPolicy = Bytes, F: FormatPolicy = AllowAll> {
+        inner: Arc<str>,
+        logical_len: usize,
+        _marker: PhantomData<(L, F)>,
+    }
+
+    unsafe impl<const MIN: usize, const MAX: usize, L: LengthPolicy, F: FormatPolicy> Send for SharedBoundedStr<MIN, MAX, L, F> {}
+    unsafe impl<const MIN: usize, const MAX: usize, L: LengthPolicy, F: FormatPolicy> Sync for SharedBoundedStr<MIN, MAX, L, F> {}
+    impl<const MIN: usize, const MAX: usize, L: LengthPolicy, F: FormatPolicy> UnwindSafe for SharedBoundedStr<MIN, MAX, L, F> {}
+    impl<const MIN: usize, const MAX: usize, L: LengthPolicy, F: FormatPolicy> RefUnwindSafe for SharedBoundedStr<MIN, MAX, L, F> {}
+
+    // Owned, always-heap view used for validation and copy-on-write edits.
+    type Owned<const MIN: usize, const MAX: usize, L, F> = HeapStr<MIN, MAX, L, F>;
+
+    impl<const MIN: usize, const MAX: usize, L: LengthPolicy, F: FormatPolicy> SharedBoundedStr<MIN, MAX, L, F> {
+        pub fn new(s: &str) -> Result<Self, BoundedStrError> {
+            let logical_len = Owned::<MIN, MAX, L, F>::validate(s)?;
+            Ok(Self { inner: Arc::from(s), logical_len, _marker: PhantomData })
+        }
+
+        #[inline(always)]
+        pub fn as_str(&self) -> &str {
+            &self.inner
+        }
+
+        #[inline(always)]
+        pub fn len_bytes(&self) -> usize {
+            self.inner.len()
+        }
+
+        #[inline(always)]
+        pub fn len_logical(&self) -> usize {
+            self.logical_len
+        }
+
+        // Whether both values point at the same shared buffer.
+        #[inline(always)]
+        pub fn ptr_eq(this: &Self, other: &Self) -> bool {
+            Arc::ptr_eq(&this.inner, &other.inner)
+        }
+
+        // Copy-on-write `BoundedStr::mutate`: the closure edits a private copy,
+        // which replaces the shared buffer only if it passes the checks. Other
+        // clones keep the old content either way.
+        pub fn mutate<Mut, R>(&mut self, mutator: Mut) -> Result<R, BoundedStrError>
+        where
+            Mut: FnOnce(&mut [u8], &mut usize) -> R,
+        {
+            let mut owned = Owned::<MIN, MAX, L, F>::from_parts(Storage::heap(self.inner.as_bytes().to_vec()), self.logical_len);
+            let res = owned.mutate(mutator)?;
+            self.inner = Arc::from(owned.as_str());
+            self.logical_len = owned.len_logical();
+            Ok(res)
+        }
+
+        // Copies the content into an owned value with its own buffer.
+        pub fn to_bounded<const MAX_BYTES: usize>(&self) -> BoundedStr<MIN, MAX, MAX_BYTES, L, F> {
+            let bytes = self.inner.as_bytes();
+            let storage = if bytes.len() <= MAX_BYTES { Storage::stack(bytes) } else { Storage::heap(bytes.to_vec()) };
+            BoundedStr::from_parts(storage, self.logical_len)
+        }
+    }
+
+    // Secrets stay out: their buffers could not be wiped once shared.
+    impl<const MIN: usize, const MAX: usize, const MAX_BYTES: usize, L: LengthPolicy, F: FormatPolicy, C: EqPolicy>
+        From<BoundedStr<MIN, MAX, MAX_BYTES, L, F, false, C>> for SharedBoundedStr<MIN, MAX, L, F>
+    {
+        fn from(value: BoundedStr<MIN, MAX, MAX_BYTES, L, F, false, C>) -> Self {
+            Self { inner: Arc::from(value.as_str()), logical_len: value.len_logical(), _marker: PhantomData }
+        }
+    }
+
+    impl<const MIN: usize, const MAX: usize, L: LengthPolicy, F: FormatPolicy> TryFrom<&str> for SharedBoundedStr<MIN, MAX, L, F> {
+        type Error = BoundedStrError;
+        fn try_from(s: &str) -> Result<Self, Self::Error> { Self::new(s) }
+    }
+
+    impl<const MIN: usize, const MAX: usize, L: LengthPolicy, F: FormatPolicy> Clone for SharedBoundedStr<MIN, MAX, L, F> {
+        fn clone(&self) -> Self {
+            Self { inner: Arc::clone(&self.inner), logical_len: self.logical_len, _marker: PhantomData }
+        }
+    }
+
+    impl<const MIN: usize, const MAX: usize, L: LengthPolicy, F: FormatPolicy> Deref for SharedBoundedStr<MIN, MAX, L, F> {
+        type Target = str;
+        fn deref(&self) -> &str { self.as_str() }
+    }
+
+    impl<const MIN: usize, const MAX: usize, L: LengthPolicy, F: FormatPolicy> AsRef<str> for SharedBoundedStr<MIN, MAX, L, F> {
+        fn as_ref(&self) -> &str { self.as_str() }
+    }
+
+    impl<const MIN: usize, const MAX: usize, L: LengthPolicy, F: FormatPolicy> Borrow<str> for SharedBoundedStr<MIN, MAX, L, F> {
+        fn borrow(&self) -> &str { self.as_str() }
+    }
+
+    impl<const MIN: usize, const MAX: usize, L: LengthPolicy, F: FormatPolicy> PartialEq for SharedBoundedStr<MIN, MAX, L, F> {
+        fn eq(&self, other: &Self) -> bool { self.inner == other.inner }
+    }
+
+    impl<const MIN: usize, const MAX: usize, L: LengthPolicy, F: FormatPolicy> Eq for SharedBoundedStr<MIN, MAX, L, F> {}
+
+    impl<const MIN: usize, const MAX: usize, L: LengthPolicy, F: FormatPolicy> PartialEq<&str> for SharedBoundedStr<MIN, MAX, L, F> {
+        fn eq(&self, other: &&str) -> bool { self.as_str() == *other }
+    }
+
+    impl<const MIN: usize, const MAX: usize, L: LengthPolicy, F: FormatPolicy> Ord for SharedBoundedStr<MIN, MAX, L, F> {
+        fn cmp(&self, other: &Self) -> Ordering { self.as_str().cmp(other.as_str()) }
+    }
+
+    impl<const MIN: usize, const MAX: usize, L: LengthPolicy, F: FormatPolicy> PartialOrd for SharedBoundedStr<MIN, MAX, L, F> {
+        fn partial_cmp(&self, other: &Self) -> Option<Ordering> { Some(self.cmp(other)) }
+    }
+
+    impl<const MIN: usize, const MAX: usize, L: LengthPolicy, F: FormatPolicy> Hash for SharedBoundedStr<MIN, MAX, L, F> {
+        fn hash<H: Hasher>(&self, state: &mut H) { self.as_str().hash(state) }
+    }
+
+    impl<const MIN: usize, const MAX: usize, L: LengthPolicy, F: FormatPolicy> Display for SharedBoundedStr<MIN, MAX, L, F> {
+        fn fmt(&self, f: &mut Formatter<'_>) -> fmt::Result { f.pad(self.as_str()) }
+    }
+
+    impl<const MIN: usize, const MAX: usize, L: LengthPolicy, F: FormatPolicy> fmt::Debug for SharedBoundedStr<MIN, MAX, L, F> {
+        fn fmt(&self, f: &mut Formatter<'_>) -> fmt::Result {
+            f.debug_struct("SharedBoundedStr")
+                .field("value", &self.as_str())
+                .field("len_bytes", &self.len_bytes())
+                .field("len_logical", &self.len_logical())
+                .finish()
+        }
+    }
+}
+
+
+// Heap-only sibling of `FlexStr` whose buffer comes from a custom allocator.
+#[cfg(feature = "allocator-api2")]
+pub use alloc_in::BoundedStrIn;
+
+// Request-scoped values in a `bumpalo::Bump`: drop them all, then `reset()`
+// the arena to free every buffer at once.
+#[cfg(feature = "bumpalo")]
+pub type BumpStr<'bump, const MIN: usize, const MAX: usize, L = Bytes, F = AllowAll> = BoundedStrIn<MIN, MAX, L, F, &'bump bumpalo::Bump>;
+
+#[cfg(feature = "allocator-api2")]
+mod alloc_in {
+    use super::*;
+    use allocator_api2::{
+        alloc::{Allocator, Global},
+        vec::Vec as VecIn,
+    };
+
+    // Backed by `allocator_api2::vec::Vec<u8, A>`, so the buffer can live in an
+    // arena, a huge-page pool or an embedded heap. `allocator-api2` re-exports
+    // `core::alloc::Allocator` with its `nightly` feature. Values are heap-only:
+    // an inline buffer would defeat the point of choosing the allocator, and
+    // there is no `Z` parameter, as arenas don't free (or wipe) single values.
+    pub struct BoundedStrIn<const MIN: usize, const MAX: usize, L: LengthPolicy = Bytes, F: FormatPolicy = AllowAll, A: Allocator = Global> {
+        buf: VecIn<u8, A>,
+        logical_len: usize,
+        _marker: PhantomData<(L, F)>,
+    }
+
+    // The allocator is stored, so it is the only parameter these depend on.
+    unsafe impl<const MIN: usize, const MAX: usize, L: LengthPolicy, F: FormatPolicy, A: Allocator + Send> Send for BoundedStrIn<MIN, MAX, L, F, A> {}
+    unsafe impl<const MIN: usize, const MAX: usize, L: LengthPolicy, F: FormatPolicy, A: Allocator + Sync> Sync for BoundedStrIn<MIN, MAX, L, F, A> {}
+    impl<const MIN: usize, const MAX: usize, L: LengthPolicy, F: FormatPolicy, A: Allocator + UnwindSafe> UnwindSafe for BoundedStrIn<MIN, MAX, L, F, A> {}
+    impl<const MIN: usize, const MAX: usize, L: LengthPolicy, F: FormatPolicy, A: Allocator + RefUnwindSafe> RefUnwindSafe for BoundedStrIn<MIN, MAX, L, F, A> {}
+
+    impl<const MIN: usize, const MAX: usize, L: LengthPolicy, F: FormatPolicy, A: Allocator> BoundedStrIn<MIN, MAX, L, F, A> {
+        pub fn new_in(s: &str, alloc: A) -> Result<Self, BoundedStrError> {
+            const { assert!(MIN <= MAX, "MIN must be <= MAX") };
+            let logical_len = validate_bounds::<MIN, MAX, L, F>(s)?;
+            let mut buf = VecIn::with_capacity_in(s.len(), alloc);
+            buf.extend_from_slice(s.as_bytes());
+            Ok(Self { buf, logical_len, _marker: PhantomData })
+        }
+
+        #[inline(always)]
+        pub fn as_str(&self) -> &str {
+            unsafe { str::from_utf8_unchecked(&self.buf) }
+        }
+
+        #[inline(always)]
+        pub fn len_bytes(&self) -> usize {
+            self.buf.len()
+        }
+
+        #[inline(always)]
+        pub fn len_logical(&self) -> usize {
+            self.logical_len
+        }
+
+        #[inline(always)]
+        pub fn capacity_bytes(&self) -> usize {
+            self.buf.capacity()
+        }
+
+        #[inline(always)]
+        pub fn allocator(&self) -> &A {
+            self.buf.allocator()
+        }
+
+        // Appends in place and cuts back if the result is rejected.
+        pub fn try_push_str(&mut self, s: &str) -> Result<(), BoundedStrError> {
+            let end = self.buf.len();
+            self.buf.extend_from_slice(s.as_bytes());
+            match validate_bounds::<MIN, MAX, L, F>(self.as_str()) {
+                Ok(n) => {
+                    self.logical_len = n;
+                    Ok(())
+                }
+                Err(e) => {
+                    self.buf.truncate(end);
+                    Err(e)
+                }
+            }
+        }
+
+        // Same contract as `BoundedStr::mutate`. The closure edits a copy in the
+        // same allocator, which replaces the buffer only if it passes the checks.
+        pub fn mutate<Mut, R>(&mut self, mutator: Mut) -> Result<R, BoundedStrError>
+        where
+            Mut: FnOnce(&mut [u8], &mut usize) -> R,
+            A: Clone,
+        {
+            let old_len = self.buf.len();
+            let size = core::cmp::max(MAX, old_len);
+            let mut work = VecIn::with_capacity_in(size, self.buf.allocator().clone());
+            work.extend_from_slice(&self.buf);
+            work.resize(size, 0);
+            let mut new_len = old_len;
+            let res = mutator(&mut work, &mut new_len);
+            if new_len > size {
+                return Err(BoundedStrError::TooManyBytes { actual: new_len, max_bytes: size });
+            }
+            work.truncate(new_len);
+            self.logical_len = BoundedStr::<MIN, MAX, 0, L, F>::check_mutation(&work)?;
+            self.buf = work;
+            Ok(res)
+        }
+
+        // Copies the content into a `BoundedStr` on the global heap.
+        pub fn to_bounded<const MAX_BYTES: usize>(&self) -> BoundedStr<MIN, MAX, MAX_BYTES, L, F> {
+            let mut storage = Storage::empty();
+            let _ = storage.push::<false>(&self.buf);
+            BoundedStr::from_parts(storage, self.logical_len)
+        }
+    }
+
+    impl<const MIN: usize, const MAX: usize, L: LengthPolicy, F: FormatPolicy> BoundedStrIn<MIN, MAX, L, F> {
+        pub fn new(s: &str) -> Result<Self, BoundedStrError> {
+            Self::new_in(s, Global)
+        }
+    }
+
+    impl<const MIN: usize, const MAX: usize, L: LengthPolicy, F: FormatPolicy, A: Allocator + Clone> Clone for BoundedStrIn<MIN, MAX, L, F, A> {
+        fn clone(&self) -> Self {
+            Self { buf: self.buf.clone(), logical_len: self.logical_len, _marker: PhantomData }
+        }
+    }
+
+    impl<const MIN: usize, const MAX: usize, L: LengthPolicy, F: FormatPolicy, A: Allocator> Deref for BoundedStrIn<MIN, MAX, L, F, A> {
+        type Target = str;
+        fn deref(&self) -> &str { self.as_str() }
+    }
+
+    impl<const MIN: usize, const MAX: usize, L: LengthPolicy, F: FormatPolicy, A: Allocator> AsRef<str> for BoundedStrIn<MIN, MAX, L, F, A> {
+        fn as_ref(&self) -> &str { self.as_str() }
+    }
+
+    impl<const MIN: usize, const MAX: usize, L: LengthPolicy, F: FormatPolicy, A: Allocator> PartialEq for BoundedStrIn<MIN, MAX, L, F, A> {
+        fn eq(&self, other: &Self) -> bool { self.as_str() == other.as_str() }
+    }
+
+    impl<const MIN: usize, const MAX: usize, L: LengthPolicy, F: FormatPolicy, A: Allocator> Eq for BoundedStrIn<MIN, MAX, L, F, A> {}
+
+    impl<const MIN: usize, const MAX: usize, L: LengthPolicy, F: FormatPolicy, A: Allocator> PartialEq<&str> for BoundedStrIn<MIN, MAX, L, F, A> {
+        fn eq(&self, other: &&str) -> bool { self.as_str() == *other }
+    }
+
+    impl<const MIN: usize, const MAX: usize, L: LengthPolicy, F: FormatPolicy, A: Allocator> Hash for BoundedStrIn<MIN, MAX, L, F, A> {
+        fn hash<H: Hasher>(&self, state: &mut H) { self.as_str().hash(state) }
+    }
+
+    impl<const MIN: usize, const MAX: usize, L: LengthPolicy, F: FormatPolicy, A: Allocator> Display for BoundedStrIn<MIN, MAX, L, F, A> {
+        fn fmt(&self, f: &mut Formatter<'_>) -> fmt::Result { f.pad(self.as_str()) }
+    }
+
+    impl<const MIN: usize, const MAX: usize, L: LengthPolicy, F: FormatPolicy, A: Allocator> fmt::Debug for BoundedStrIn<MIN, MAX, L, F, A> {
+        fn fmt(&self, f: &mut Formatter<'_>) -> fmt::Result {
+            f.debug_struct("BoundedStrIn")
+                .field("value", &self.as_str())
+                .field("len_bytes", &self.len_bytes())
+                .field("len_logical", &self.len_logical())
+                .finish()
+        }
+    }
+}
+
+// C ABI for passing validated strings across an FFI boundary. C callers pass
+// bounds in bytes at run time; Rust code hands over values of any `Z = false`
+// type with `BoundedStrHandle::into_raw`. Content is not NUL-terminated.
+// Safety contracts are spelled out in plain comments, as elsewhere in the crate.
+#[cfg(feature = "ffi")]
+#[allow(clippy::missing_safety_doc)]
+pub mod ffi {
+    use super::*;
+    use alloc::boxed::Box;
+
+    type Inner = HeapStr<0, { usize::MAX }>;
+
+    // Opaque: only ever used behind a pointer, which really points at a boxed
+    // `Inner`. The private field keeps it from being built or moved by value,
+    // and C sees an incomplete `BoundedStrHandle *`.
+    #[repr(C)]
+    pub struct BoundedStrHandle {
+        _private: [u8; 0],
+        _marker: PhantomData<(*mut u8, core::marker::PhantomPinned)>,
+    }
+
+    #[repr(C)]
+    #[derive(Debug, Clone, Copy, PartialEq, Eq)]
+    pub enum BoundedStrStatus {
+        Ok = 0,
+        NullPointer = 1,
+        InvalidUtf8 = 2,
+        TooShort = 3,
+        TooLong = 4,
+    }
+
+    impl BoundedStrHandle {
+        // Free the result with `bounded_str_free`.
+        pub fn into_raw<const MIN: usize, const MAX: usize, const MAX_BYTES: usize, L: LengthPolicy, F: FormatPolicy, C: EqPolicy>(
+            s: BoundedStr<MIN, MAX, MAX_BYTES, L, F, false, C>,
+        ) -> *mut Self {
+            let logical_len = s.len_bytes();
+            Self::from_inner(Inner::from_parts(Storage::heap(s.into_bytes()), logical_len))
+        }
+
+        // `handle` must be a live handle; the result borrows from it until it is freed.
+        #[inline(always)]
+        pub unsafe fn as_str<'a>(handle: *const Self) -> &'a str {
+            unsafe { (*handle.cast::<Inner>()).as_str() }
+        }
+
+        fn from_inner(value: Inner) -> *mut Self {
+            Box::into_raw(Box::new(value)).cast()
+        }
+
+        // `handle` must be null or a live handle.
+        unsafe fn inner<'a>(handle: *const Self) -> Option<&'a Inner> {
+            unsafe { handle.cast::<Inner>().as_ref() }
+        }
+    }
+
+    // Validates `len` bytes at `ptr` as UTF-8 of `min..=max` bytes and writes
+    // a new handle to `*out`. `*out` is left untouched on failure.
+    //
+    // Safety: `ptr` must be valid for reads of `len` bytes (it may be null if
+    // `len` is 0) and `out` must be valid for a pointer write.
+    #[unsafe(no_mangle)]
+    pub unsafe extern "C" fn bounded_str_new(
+        ptr: *const u8,
+        len: usize,
+        min: usize,
+        max: usize,
+        out: *mut *mut BoundedStrHandle,
+    ) -> BoundedStrStatus {
+        if out.is_null() || (ptr.is_null() && len != 0) {
+            return BoundedStrStatus::NullPointer;
+        }
+        let bytes = if len == 0 { &[][..] } else { unsafe { core::slice::from_raw_parts(ptr, len) } };
+        let Some(s) = utf8_str(bytes) else { return BoundedStrStatus::InvalidUtf8 };
+        if len < min { return BoundedStrStatus::TooShort; }
+        if len > max { return BoundedStrStatus::TooLong; }
+        let value = Inner::from_parts(Storage::heap(s.as_bytes().to_vec()), len);
+        unsafe { out.write(BoundedStrHandle::from_inner(value)) };
+        BoundedStrStatus::Ok
+    }
+
+    // Frees a handle from `bounded_str_new` or `BoundedStrHandle::into_raw`.
+    // Null is ignored.
+    //
+    // Safety: `handle` must be null or a live handle, and must not be used afterwards.
+    #[unsafe(no_mangle)]
+    pub unsafe extern "C" fn bounded_str_free(handle: *mut BoundedStrHandle) {
+        if !handle.is_null() {
+            drop(unsafe { Box::from_raw(handle.cast::<Inner>()) });
+        }
+    }
+
+    // Start of the UTF-8 content, valid until the handle is freed; null for a
+    // null handle.
+    //
+    // Safety: `handle` must be null or a live handle.
+    #[unsafe(no_mangle)]
+    pub unsafe extern "C" fn bounded_str_as_ptr(handle: *const BoundedStrHandle) -> *const u8 {
+        match unsafe { BoundedStrHandle::inner(handle) } {
+            Some(h) => h.as_bytes().as_ptr(),
+            None => core::ptr::null(),
+        }
+    }
+
+    // Length of the content in bytes; 0 for a null handle.
+    //
+    // Safety: `handle` must be null or a live handle.
+    #[unsafe(no_mangle)]
+    pub unsafe extern "C" fn bounded_str_len(handle: *const BoundedStrHandle) -> usize {
+        match unsafe { BoundedStrHandle::inner(handle) } {
+            Some(h) => h.len_bytes(),
+            None => 0,
+        }
+    }
+}